@@ -0,0 +1,192 @@
+// grpc_project_build/src/client.rs
+use prost_build::{Method, Service, ServiceGenerator};
+use std::fmt::Write;
+
+/// Generates a `<Service>Client` for each service, with one method per rpc
+/// that calls through a `grpc_project::Client`. Streaming requests are taken
+/// as any `Stream` of messages, and streaming responses are returned as a
+/// `Streaming` to read them from.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClientGenerator;
+
+impl ServiceGenerator for ClientGenerator {
+    fn generate(&mut self, service: Service, buf: &mut String) {
+        let client = format!("{}Client", service.name);
+
+        service.comments.append_with_indent(0, buf);
+        if !service.comments.leading.is_empty() || !service.comments.trailing.is_empty() {
+            buf.push_str("///\n");
+        }
+        let _ = writeln!(
+            buf,
+            "/// Typed client for the `{}` service.",
+            full_name(&service)
+        );
+        let _ = writeln!(
+            buf,
+            "#[derive(Clone, Debug)]
+pub struct {client} {{
+    inner: ::grpc_project::codegen::Client,
+}}
+
+impl {client} {{
+    pub fn new(inner: ::grpc_project::codegen::Client) -> Self {{
+        Self {{ inner }}
+    }}
+
+    /// The client calls are made with.
+    pub fn inner(&self) -> &::grpc_project::codegen::Client {{
+        &self.inner
+    }}
+"
+        );
+        for method in &service.methods {
+            generate_method(&service, method, buf);
+        }
+        let _ = writeln!(
+            buf,
+            "}}
+
+impl From<::grpc_project::codegen::Client> for {client} {{
+    fn from(inner: ::grpc_project::codegen::Client) -> Self {{
+        Self::new(inner)
+    }}
+}}"
+        );
+    }
+}
+
+fn generate_method(service: &Service, method: &Method, buf: &mut String) {
+    let call = match (method.client_streaming, method.server_streaming) {
+        (false, false) => "unary",
+        (false, true) => "server_streaming",
+        (true, false) => "client_streaming",
+        (true, true) => "streaming",
+    };
+    let output = match method.server_streaming {
+        true => format!("::grpc_project::codegen::Streaming<{}>", method.output_type),
+        false => method.output_type.clone(),
+    };
+    let (generics, parameter, argument, bound) = match method.client_streaming {
+        true => (
+            "<S>",
+            "requests: S".to_string(),
+            "requests",
+            format!(
+                "
+    where
+        S: ::grpc_project::codegen::Stream<Item = {}> + Send + 'static,
+    ",
+                method.input_type
+            ),
+        ),
+        false => (
+            "",
+            format!("request: {}", method.input_type),
+            "request",
+            " ".to_string(),
+        ),
+    };
+
+    method.comments.append_with_indent(1, buf);
+    let _ = writeln!(
+        buf,
+        "    pub async fn {name}{generics}(
+        &self,
+        {parameter},
+        options: impl Into<::grpc_project::codegen::CallOptions>,
+    ) -> ::grpc_project::codegen::Result<::grpc_project::codegen::Response<{output}>>{bound}{{
+        self.inner.{call}(\"{path}\", {argument}, options).await
+    }}
+",
+        name = method.name,
+        path = format_args!("/{}/{}", full_name(service), method.proto_name),
+    );
+}
+
+/// `package.Service`, or just `Service` outside a package.
+fn full_name(service: &Service) -> String {
+    match service.package.as_str() {
+        "" => service.proto_name.clone(),
+        package => format!("{}.{}", package, service.proto_name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_build::Comments;
+
+    fn method(
+        name: &str,
+        proto_name: &str,
+        client_streaming: bool,
+        server_streaming: bool,
+    ) -> Method {
+        Method {
+            name: name.to_string(),
+            proto_name: proto_name.to_string(),
+            comments: Comments::default(),
+            input_type: "HelloRequest".to_string(),
+            output_type: "HelloReply".to_string(),
+            input_proto_type: ".helloworld.HelloRequest".to_string(),
+            output_proto_type: ".helloworld.HelloReply".to_string(),
+            options: Default::default(),
+            client_streaming,
+            server_streaming,
+        }
+    }
+
+    fn generate(methods: Vec<Method>) -> String {
+        let service = Service {
+            name: "Greeter".to_string(),
+            proto_name: "Greeter".to_string(),
+            package: "helloworld".to_string(),
+            comments: Comments::default(),
+            methods,
+            options: Default::default(),
+        };
+        let mut buf = String::new();
+        ClientGenerator.generate(service, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_unary_methods_call_the_full_path() {
+        let buf = generate(vec![method("say_hello", "SayHello", false, false)]);
+
+        assert!(buf.contains("pub struct GreeterClient"));
+        assert!(buf.contains("pub async fn say_hello("));
+        assert!(buf.contains("request: HelloRequest,"));
+        assert!(buf.contains("Response<HelloReply>>"));
+        assert!(
+            buf.contains("self.inner.unary(\"/helloworld.Greeter/SayHello\", request, options)")
+        );
+    }
+
+    #[test]
+    fn test_streaming_methods_use_the_matching_call() {
+        let buf = generate(vec![
+            method("lots_of_replies", "LotsOfReplies", false, true),
+            method("lots_of_greetings", "LotsOfGreetings", true, false),
+            method("bidi_hello", "BidiHello", true, true),
+        ]);
+
+        assert!(buf.contains("pub async fn lots_of_replies("));
+        assert!(buf.contains(
+            "self.inner.server_streaming(\"/helloworld.Greeter/LotsOfReplies\", request, options)"
+        ));
+        assert!(buf.contains("pub async fn lots_of_greetings<S>("));
+        assert!(buf.contains("self.inner.client_streaming(\"/helloworld.Greeter/LotsOfGreetings\", requests, options)"));
+        assert!(buf.contains("pub async fn bidi_hello<S>("));
+        assert!(buf.contains(
+            "self.inner.streaming(\"/helloworld.Greeter/BidiHello\", requests, options)"
+        ));
+        assert!(buf.contains("Stream<Item = HelloRequest> + Send + 'static,"));
+        assert_eq!(
+            buf.matches("Response<::grpc_project::codegen::Streaming<HelloReply>>>")
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// grpc_project_build/src/lib.rs
+mod client;
+
+use std::io::Result;
+use std::path::{Path, PathBuf};
+
+pub use client::ClientGenerator;
+
+/// Compiles `protos` into message types and typed client stubs, written to
+/// `OUT_DIR` as one `<package>.rs` file per protobuf package. Meant to be
+/// called from a build script:
+///
+/// ```no_run
+/// // build.rs
+/// fn main() -> std::io::Result<()> {
+///     grpc_project_build::compile_protos(&["proto/helloworld.proto"], &["proto"])
+/// }
+/// ```
+///
+/// The generated file is included with `grpc_project::include_proto!`.
+/// Like `prost-build`, this runs `protoc`, found through the `PROTOC`
+/// environment variable or on the `PATH`.
+pub fn compile_protos(protos: &[impl AsRef<Path>], includes: &[impl AsRef<Path>]) -> Result<()> {
+    configure().compile(protos, includes)
+}
+
+/// Starts a [`Builder`] with the default settings.
+pub fn configure() -> Builder {
+    Builder::default()
+}
+
+/// Settings for generating code from `.proto` files.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    build_client: bool,
+    out_dir: Option<PathBuf>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            build_client: true,
+            out_dir: None,
+        }
+    }
+}
+
+impl Builder {
+    /// Whether to generate client stubs for services; on by default. Without
+    /// them only the message types are generated.
+    pub fn build_client(mut self, enable: bool) -> Self {
+        self.build_client = enable;
+        self
+    }
+
+    /// Writes the generated files to `out_dir` instead of `OUT_DIR`.
+    pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Self {
+        self.out_dir = Some(out_dir.into());
+        self
+    }
+
+    pub fn compile(self, protos: &[impl AsRef<Path>], includes: &[impl AsRef<Path>]) -> Result<()> {
+        self.compile_with_config(prost_build::Config::new(), protos, includes)
+    }
+
+    /// Like [`compile`](Self::compile), with a `prost_build::Config` for
+    /// settings such as extra attributes on the message types.
+    pub fn compile_with_config(
+        self,
+        mut config: prost_build::Config,
+        protos: &[impl AsRef<Path>],
+        includes: &[impl AsRef<Path>],
+    ) -> Result<()> {
+        if let Some(out_dir) = &self.out_dir {
+            config.out_dir(out_dir);
+        }
+        if self.build_client {
+            config.service_generator(Box::new(ClientGenerator));
+        }
+        for proto in protos {
+            println!("cargo:rerun-if-changed={}", proto.as_ref().display());
+        }
+        config.compile_protos(protos, includes)
+    }
+}
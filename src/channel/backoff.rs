@@ -0,0 +1,113 @@
+// src/channel/backoff.rs
+use rand::Rng;
+use std::time::Duration;
+
+/// Reconnect backoff parameters, following the gRPC connection backoff spec.
+#[derive(Clone, Debug)]
+pub struct BackoffConfig {
+    pub base_delay: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    pub max_delay: Duration,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(1),
+            multiplier: 1.6,
+            jitter: 0.2,
+            max_delay: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Yields the delay before each successive reconnect attempt.
+#[derive(Debug)]
+pub(crate) struct Backoff {
+    config: BackoffConfig,
+    current: Duration,
+}
+
+impl Backoff {
+    pub(crate) fn new(config: BackoffConfig) -> Self {
+        let current = config.base_delay;
+        Self { config, current }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.current = self.config.base_delay;
+    }
+
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        // In f64 so a large multiplier saturates at `max_delay` instead of
+        // overflowing `Duration`.
+        let max_delay = self.config.max_delay;
+        self.current = scale(self.current, self.config.multiplier)
+            .unwrap_or(max_delay)
+            .min(max_delay);
+
+        if self.config.jitter > 0.0 {
+            let jitter = rand::thread_rng().gen_range(-self.config.jitter..=self.config.jitter);
+            scale(delay, 1.0 + jitter).unwrap_or(delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// `duration * factor`, or `None` when the result is not a valid duration.
+pub(crate) fn scale(duration: Duration, factor: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(duration.as_secs_f64() * factor).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_to_max() {
+        let mut backoff = Backoff::new(BackoffConfig {
+            base_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            jitter: 0.0,
+            max_delay: Duration::from_millis(500),
+        });
+
+        let delays: Vec<_> = (0..5).map(|_| backoff.next_delay()).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 500, 500]
+                .map(Duration::from_millis)
+                .to_vec()
+        );
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_backoff_saturates_instead_of_overflowing() {
+        let mut backoff = Backoff::new(BackoffConfig {
+            base_delay: Duration::from_secs(1),
+            multiplier: 1e300,
+            jitter: 0.0,
+            max_delay: Duration::from_secs(120),
+        });
+
+        let delays: Vec<_> = (0..3).map(|_| backoff.next_delay()).collect();
+        assert_eq!(delays, [1, 120, 120].map(Duration::from_secs).to_vec());
+    }
+
+    #[test]
+    fn test_backoff_jitter_stays_in_range() {
+        let mut backoff = Backoff::new(BackoffConfig::default());
+        for _ in 0..100 {
+            let delay = backoff.next_delay();
+            backoff.reset();
+            assert!(delay >= Duration::from_millis(800));
+            assert!(delay <= Duration::from_millis(1200));
+        }
+    }
+}
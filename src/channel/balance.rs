@@ -0,0 +1,144 @@
+// src/channel/balance.rs
+use super::state::ConnectivityState;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Chooses which subchannel serves each call.
+///
+/// Both methods receive the connectivity state of every subchannel, in
+/// address order, and answer with indices into that slice.
+pub trait LoadBalancer: fmt::Debug + Send + Sync + 'static {
+    /// Picks a `Ready` subchannel for the next call, or `None` if the call
+    /// has to wait for one.
+    fn pick(&self, states: &[ConnectivityState]) -> Option<usize>;
+
+    /// Idle subchannels that should start connecting.
+    fn connect(&self, states: &[ConnectivityState]) -> Vec<usize>;
+}
+
+/// Built-in load-balancing policies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoadBalancingPolicy {
+    /// Use the first address that connects; move on to the next one only
+    /// when it fails.
+    #[default]
+    PickFirst,
+    /// Connect to every address and rotate calls across the ready ones.
+    RoundRobin,
+}
+
+impl LoadBalancingPolicy {
+    pub(crate) fn build(self) -> Box<dyn LoadBalancer> {
+        match self {
+            LoadBalancingPolicy::PickFirst => Box::new(PickFirst),
+            LoadBalancingPolicy::RoundRobin => Box::new(RoundRobin::default()),
+        }
+    }
+}
+
+/// Sends every call to the first ready subchannel, connecting to one address
+/// at a time.
+#[derive(Debug, Default)]
+pub struct PickFirst;
+
+impl LoadBalancer for PickFirst {
+    fn pick(&self, states: &[ConnectivityState]) -> Option<usize> {
+        states
+            .iter()
+            .position(|state| *state == ConnectivityState::Ready)
+    }
+
+    fn connect(&self, states: &[ConnectivityState]) -> Vec<usize> {
+        let busy = states.iter().any(|state| {
+            matches!(
+                state,
+                ConnectivityState::Ready | ConnectivityState::Connecting
+            )
+        });
+        if busy {
+            return Vec::new();
+        }
+        states
+            .iter()
+            .position(|state| *state == ConnectivityState::Idle)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Keeps every subchannel connected and spreads calls across the ready ones
+/// in turn.
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl LoadBalancer for RoundRobin {
+    fn pick(&self, states: &[ConnectivityState]) -> Option<usize> {
+        let ready: Vec<usize> = states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state == ConnectivityState::Ready)
+            .map(|(index, _)| index)
+            .collect();
+        if ready.is_empty() {
+            return None;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        Some(ready[next % ready.len()])
+    }
+
+    fn connect(&self, states: &[ConnectivityState]) -> Vec<usize> {
+        states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state == ConnectivityState::Idle)
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Connectivity state of a channel as a whole: `Ready` if any subchannel is
+/// ready, otherwise the most hopeful state among them.
+pub(crate) fn aggregate(states: &[ConnectivityState]) -> ConnectivityState {
+    use ConnectivityState::*;
+
+    [Ready, Connecting, Idle, TransientFailure]
+        .into_iter()
+        .find(|candidate| states.contains(candidate))
+        .unwrap_or(Shutdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ConnectivityState::*;
+
+    #[test]
+    fn test_pick_first_prefers_earliest_ready() {
+        let balancer = PickFirst;
+        assert_eq!(balancer.pick(&[TransientFailure, Ready, Ready]), Some(1));
+        assert_eq!(balancer.pick(&[Idle, Connecting]), None);
+
+        assert_eq!(balancer.connect(&[TransientFailure, Idle, Idle]), vec![1]);
+        assert!(balancer.connect(&[Connecting, Idle]).is_empty());
+    }
+
+    #[test]
+    fn test_round_robin_rotates_over_ready() {
+        let balancer = RoundRobin::default();
+        let states = [Ready, TransientFailure, Ready];
+        let picks: Vec<_> = (0..4).filter_map(|_| balancer.pick(&states)).collect();
+        assert_eq!(picks, vec![0, 2, 0, 2]);
+
+        assert_eq!(balancer.connect(&[Idle, Ready, Idle]), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_aggregate_state() {
+        assert_eq!(aggregate(&[TransientFailure, Ready]), Ready);
+        assert_eq!(aggregate(&[TransientFailure, Idle, Connecting]), Connecting);
+        assert_eq!(aggregate(&[TransientFailure, Shutdown]), TransientFailure);
+        assert_eq!(aggregate(&[Shutdown]), Shutdown);
+    }
+}
@@ -0,0 +1,122 @@
+// src/channel/bdp.rs
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Largest window the estimator grows to.
+pub(crate) const BDP_LIMIT: u32 = 16 * 1024 * 1024;
+
+// Weight of the newest round trip once the first samples are averaged in.
+const ALPHA: f64 = 0.9;
+// Fraction of the current estimate a sample must reach to grow it.
+const BETA: f64 = 0.66;
+// Headroom given on top of a sample when the estimate grows.
+const GAMMA: f64 = 2.0;
+// Round trips averaged evenly before the smoothed RTT takes over.
+const BOOTSTRAP_SAMPLES: u32 = 10;
+
+/// Bandwidth-delay product estimator, following grpc-go's.
+///
+/// Each sample is the number of DATA bytes received while one PING was in
+/// flight. A saturated link delivers about one window per round trip, so a
+/// sample close to the current estimate at the highest bandwidth seen so far
+/// means the window is the bottleneck and should grow.
+#[derive(Debug)]
+pub(crate) struct Estimator {
+    bdp: u32,
+    rtt: f64,
+    bandwidth_max: f64,
+    samples: u32,
+}
+
+impl Estimator {
+    pub(crate) fn new(window: u32) -> Self {
+        Self {
+            bdp: window.min(BDP_LIMIT),
+            rtt: 0.0,
+            bandwidth_max: 0.0,
+            samples: 0,
+        }
+    }
+
+    /// Folds in `bytes` received over a PING round trip of `rtt`. Returns
+    /// the new window size when the estimate grows.
+    pub(crate) fn sample(&mut self, bytes: u64, rtt: Duration) -> Option<u32> {
+        self.samples += 1;
+        let rtt = rtt.as_secs_f64();
+        if self.samples < BOOTSTRAP_SAMPLES {
+            self.rtt += (rtt - self.rtt) / f64::from(self.samples);
+        } else {
+            self.rtt += (rtt - self.rtt) * ALPHA;
+        }
+        if self.rtt <= 0.0 {
+            return None;
+        }
+
+        // A sample holds at most about 1.5 round trips' worth of data.
+        let bytes = bytes as f64;
+        let bandwidth = bytes / (self.rtt * 1.5);
+        if bandwidth > self.bandwidth_max {
+            self.bandwidth_max = bandwidth;
+        }
+
+        let grow = bytes >= BETA * f64::from(self.bdp)
+            && bandwidth == self.bandwidth_max
+            && self.bdp < BDP_LIMIT;
+        if !grow {
+            return None;
+        }
+        self.bdp = (GAMMA * bytes).min(f64::from(BDP_LIMIT)) as u32;
+        Some(self.bdp)
+    }
+}
+
+/// DATA bytes read by the calls on a subchannel. Each read wakes the
+/// estimator so a PING goes out while data is flowing.
+#[derive(Debug, Default)]
+pub(crate) struct Received {
+    bytes: AtomicU64,
+    notify: Notify,
+}
+
+impl Received {
+    pub(crate) fn record(&self, len: usize) {
+        self.bytes.fetch_add(len as u64, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Resolves once data has been read since the last call returned.
+    pub(crate) async fn changed(&self) {
+        self.notify.notified().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RTT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn test_estimate_grows_while_window_is_saturated() {
+        let mut estimator = Estimator::new(65_535);
+
+        // A full window per round trip doubles the estimate each time.
+        assert_eq!(estimator.sample(65_535, RTT), Some(131_070));
+        assert_eq!(estimator.sample(131_070, RTT), Some(262_140));
+
+        // Far less than the estimate means the window is not the bottleneck.
+        assert_eq!(estimator.sample(10_000, RTT), None);
+    }
+
+    #[test]
+    fn test_estimate_is_capped() {
+        let mut estimator = Estimator::new(BDP_LIMIT / 2);
+        assert_eq!(estimator.sample(u64::from(BDP_LIMIT), RTT), Some(BDP_LIMIT));
+        assert_eq!(estimator.sample(u64::from(BDP_LIMIT) * 2, RTT), None);
+    }
+}
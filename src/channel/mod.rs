@@ -0,0 +1,725 @@
+// src/channel/mod.rs
+use crate::client::ClientConfig;
+use crate::transport::cancel::{self, CancellationToken};
+use crate::transport::{
+    in_process, Body, BoxedIo, Connector, DefaultConnector, InProcessConnector, Io, ProxyConfig,
+    ProxyConnector,
+};
+use crate::{Error, Result, Status};
+use futures_util::future::{self, BoxFuture};
+use http::uri::{Authority, PathAndQuery, Scheme};
+use http::{Request, Response, Uri};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tower::Service;
+
+pub mod backoff;
+pub mod balance;
+mod bdp;
+mod preface;
+pub mod resolver;
+pub mod state;
+mod subchannel;
+
+pub use backoff::BackoffConfig;
+pub use balance::{LoadBalancer, LoadBalancingPolicy, PickFirst, RoundRobin};
+pub use resolver::{Address, DnsResolver, Resolution, Resolver, StaticResolver, Target};
+pub use state::ConnectivityState;
+
+use subchannel::{lock, Subchannel};
+
+/// A cloneable handle to one or more HTTP/2 connections.
+///
+/// A channel is created for a target such as `dns:///example.com:50051`,
+/// `ipv4:10.0.0.1:50051,10.0.0.2:50051` or `http://localhost:50051`. A
+/// [`Resolver`] turns the target into addresses and keeps them up to date; the
+/// channel holds a subchannel per address, each with its own connection, and
+/// a [`LoadBalancer`] picks the subchannel that serves each call. Every call
+/// runs on its own h2 stream, so responses are delivered straight to the
+/// caller that issued the request. At most `max_concurrent_streams` calls are
+/// in flight on each connection at once; further calls wait for a slot.
+///
+/// A background task owns each connection. When it drops, the subchannel
+/// moves to `Connecting` and redials, backing off between failed attempts,
+/// while calls go to the subchannels that are still ready. Calls made while
+/// no subchannel can connect fail fast with `Unavailable`, unless the request
+/// carries the [`WaitForReady`] extension.
+///
+/// With `ClientConfig::idle_timeout` set, a connection that carries no calls
+/// for that long is closed and its subchannel returns to `Idle`; the next call
+/// dials again.
+///
+/// When the server sends GOAWAY, streams it already accepted run to
+/// completion on the old connection while new calls move to a fresh one.
+#[derive(Clone, Debug)]
+pub struct Channel {
+    inner: Arc<Inner>,
+}
+
+/// Request extension that makes a call wait for the channel to become
+/// `Ready` instead of failing fast while it is in `TransientFailure`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WaitForReady;
+
+#[derive(Debug)]
+struct Inner {
+    subchannels: watch::Sender<Subchannels>,
+    balancer: Box<dyn LoadBalancer>,
+    config: ClientConfig,
+    // Fill in the URI of every request.
+    scheme: Scheme,
+    authority: Option<Authority>,
+    // Applies resolver updates to `subchannels`.
+    resolution: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// The subchannels for the addresses the resolvers last reported.
+#[derive(Clone, Debug, Default)]
+struct Subchannels {
+    list: Vec<Subchannel>,
+    // Why there are no addresses, once resolution has failed.
+    error: Option<String>,
+    shutdown: bool,
+}
+
+impl Channel {
+    /// Resolves `target`, then dials its addresses in order until one
+    /// completes the HTTP/2 handshake. The first attempt is made eagerly and
+    /// its failure is returned to the caller; later reconnects happen in the
+    /// background.
+    pub async fn connect<T: AsRef<str>>(target: T, config: &ClientConfig) -> Result<Self> {
+        let target = target.as_ref().parse::<Target>()?;
+        let (config, proxied) = dial_config(std::slice::from_ref(&target), config)?;
+        let config = &config;
+        let mut resolution = resolver::builtin(&target, proxied)?.resolve(&target);
+        let resolved = tokio::time::timeout(
+            config.connect_timeout,
+            resolution.wait_for(|resolution| *resolution != Resolution::Pending),
+        )
+        .await?
+        .map_err(|_| Error::ConnectionClosed)?
+        .clone();
+
+        let addrs = match resolved {
+            Resolution::Resolved(addrs) if !addrs.is_empty() => addrs,
+            Resolution::Failed(message) => return Err(resolution_failed(&message).into()),
+            _ => return Err(no_addresses(&target).into()),
+        };
+
+        let mut list = Vec::with_capacity(addrs.len());
+        let mut last_error = None;
+        for address in addrs {
+            if last_error.is_none() && !list.is_empty() {
+                list.push(Subchannel::connect_lazy(address, config));
+                continue;
+            }
+            match Subchannel::connect(address.clone(), config).await {
+                Ok(subchannel) => {
+                    last_error = None;
+                    list.push(subchannel);
+                }
+                Err(e) => {
+                    last_error = Some(e);
+                    list.push(Subchannel::connect_lazy(address, config));
+                }
+            }
+        }
+        if let Some(e) = last_error {
+            future::join_all(list.iter().map(Subchannel::shutdown)).await;
+            return Err(e);
+        }
+
+        let subchannels = Subchannels {
+            list,
+            ..Default::default()
+        };
+        Ok(Self::new(
+            subchannels,
+            vec![resolution],
+            config.load_balancing_policy.build(),
+            config,
+            &default_authority(&target),
+        ))
+    }
+
+    /// Creates a channel in the `Idle` state without dialing. The connection
+    /// is opened by the first call, so the server does not need to be up yet.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn connect_lazy<T: AsRef<str>>(target: T, config: &ClientConfig) -> Result<Self> {
+        Self::balance([target], config)
+    }
+
+    /// Creates a channel for several targets, balancing calls across all of
+    /// their addresses with `ClientConfig::load_balancing_policy`. Connections
+    /// are opened lazily, as the policy asks for them.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn balance<I, T>(targets: I, config: &ClientConfig) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        Self::balance_with(targets, config, config.load_balancing_policy.build())
+    }
+
+    /// Like [`Channel::balance`], with a custom load balancer.
+    pub fn balance_with<I, T>(
+        targets: I,
+        config: &ClientConfig,
+        balancer: Box<dyn LoadBalancer>,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let targets = targets
+            .into_iter()
+            .map(|target| target.as_ref().parse::<Target>())
+            .collect::<Result<Vec<_>>>()?;
+        if targets.is_empty() {
+            return Err(Error::Config(
+                "Channel needs at least one address".to_string(),
+            ));
+        }
+
+        let (config, proxied) = dial_config(&targets, config)?;
+        let resolutions = targets
+            .iter()
+            .map(|target| Ok(resolver::builtin(target, proxied)?.resolve(target)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(
+            Subchannels::default(),
+            resolutions,
+            balancer,
+            &config,
+            &default_authority(&targets[0]),
+        ))
+    }
+
+    /// Creates a lazy channel whose addresses come from `resolver`, which
+    /// may update them at any time.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn resolve_with<T: AsRef<str>>(
+        target: T,
+        config: &ClientConfig,
+        resolver: Box<dyn Resolver>,
+    ) -> Result<Self> {
+        let target = target.as_ref().parse::<Target>()?;
+        Ok(Self::new(
+            Subchannels::default(),
+            vec![resolver.resolve(&target)],
+            config.load_balancing_policy.build(),
+            &dial_config(std::slice::from_ref(&target), config)?.0,
+            &default_authority(&target),
+        ))
+    }
+
+    /// Creates a lazy channel whose connections are dialed through
+    /// `connector` to its in-process listener, whatever
+    /// `ClientConfig::connector` says.
+    pub fn in_process(connector: InProcessConnector, config: &ClientConfig) -> Self {
+        let mut config = config.clone();
+        config.connector = Some(Arc::new(connector));
+        let resolver = StaticResolver::new([Address::Named(in_process::ADDRESS.to_string())]);
+        let target = Target::from_str("in-process:local").expect("valid target");
+
+        Self::new(
+            Subchannels::default(),
+            vec![resolver.resolve(&target)],
+            config.load_balancing_policy.build(),
+            &config,
+            LOCALHOST,
+        )
+    }
+
+    /// Runs the HTTP/2 handshake over `stream` and uses it as the channel's
+    /// only connection. Once the stream closes the channel cannot reconnect,
+    /// and calls fail with `Unavailable`.
+    pub async fn from_stream<T: Io>(stream: T, config: &ClientConfig) -> Result<Self> {
+        let mut config = config.clone();
+        config.connector = Some(Arc::new(NoReconnect));
+        let address = Address::Named("stream".to_string());
+        let subchannel = Subchannel::from_stream(address, Box::new(stream), &config).await?;
+
+        let subchannels = Subchannels {
+            list: vec![subchannel],
+            ..Default::default()
+        };
+        Ok(Self::new(
+            subchannels,
+            Vec::new(),
+            config.load_balancing_policy.build(),
+            &config,
+            LOCALHOST,
+        ))
+    }
+
+    fn new(
+        subchannels: Subchannels,
+        mut resolutions: Vec<watch::Receiver<Resolution>>,
+        balancer: Box<dyn LoadBalancer>,
+        config: &ClientConfig,
+        default_authority: &str,
+    ) -> Self {
+        let scheme = match config.connector().is_secure() {
+            true => Scheme::HTTPS,
+            false => Scheme::HTTP,
+        };
+        let authority = config.authority.as_deref().unwrap_or(default_authority);
+        let inner = Arc::new(Inner {
+            subchannels: watch::Sender::new(subchannels),
+            balancer,
+            config: config.clone(),
+            scheme,
+            // Targets such as address lists are not authorities; their
+            // requests go out without one, as when none can be derived.
+            authority: authority.parse().ok(),
+            resolution: Mutex::new(None),
+        });
+
+        // Apply what the resolvers already know, so static targets have
+        // their subchannels before the first call.
+        let latest: Vec<_> = resolutions
+            .iter_mut()
+            .map(|resolution| resolution.borrow_and_update().clone())
+            .collect();
+        inner.update(merge(&latest));
+
+        let task = tokio::spawn(watch_resolution(
+            Arc::downgrade(&inner),
+            latest,
+            resolutions,
+        ));
+        *lock(&inner.resolution) = Some(task);
+
+        Self { inner }
+    }
+
+    /// Whether the channel's connections are secure, as reported by
+    /// `Connector::is_secure`.
+    pub fn is_secure(&self) -> bool {
+        self.inner.scheme == Scheme::HTTPS
+    }
+
+    /// Shuts the channel down gracefully. New calls fail immediately, calls
+    /// already in flight get up to `ClientConfig::drain_timeout` to finish,
+    /// and then the connections are closed. Shutdown is permanent and applies
+    /// to every clone of the channel.
+    pub async fn shutdown(&self) {
+        if let Some(task) = lock(&self.inner.resolution).take() {
+            task.abort();
+        }
+
+        let mut list = Vec::new();
+        self.inner.subchannels.send_modify(|subchannels| {
+            subchannels.shutdown = true;
+            list = subchannels.list.clone();
+        });
+        future::join_all(list.iter().map(Subchannel::shutdown)).await;
+    }
+
+    /// Current connectivity state: `Ready` if any subchannel is ready,
+    /// otherwise the most hopeful state among them.
+    pub fn state(&self) -> ConnectivityState {
+        let subchannels = self.inner.subchannels.borrow().clone();
+        let mut receivers = subchannels.subscribe();
+        subchannels.state(&current_states(&mut receivers))
+    }
+
+    /// Addresses of the subchannels, in the order the balancer sees them.
+    pub fn addrs(&self) -> Vec<Address> {
+        self.inner
+            .subchannels
+            .borrow()
+            .list
+            .iter()
+            .map(|subchannel| subchannel.address().clone())
+            .collect()
+    }
+
+    /// Interval between keepalive PINGs, or `None` when keepalive is disabled.
+    /// Grows when a server reports `too_many_pings`; with several subchannels
+    /// the longest interval is returned.
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        if !self.inner.config.enable_http2_keepalive {
+            return None;
+        }
+        self.inner
+            .subchannels
+            .borrow()
+            .list
+            .iter()
+            .filter_map(Subchannel::keepalive_interval)
+            .max()
+            .or(Some(self.inner.config.http2_keepalive_interval))
+    }
+
+    /// Waits until the state differs from `current` and returns the new state.
+    pub async fn wait_for_state_change(&self, current: ConnectivityState) -> ConnectivityState {
+        let mut list = self.inner.subchannels.subscribe();
+        loop {
+            let subchannels = list.borrow_and_update().clone();
+            let mut receivers = subchannels.subscribe();
+            let state = subchannels.state(&current_states(&mut receivers));
+            if state != current {
+                return state;
+            }
+            changed(&mut list, &mut receivers).await;
+        }
+    }
+
+    /// Picks a subchannel with the load balancer and sends `request` on it,
+    /// waiting while addresses resolve and subchannels connect.
+    async fn route(&self, request: Request<Body>) -> Result<Response<Body>> {
+        let inner = &self.inner;
+        let wait_for_ready = request.extensions().get::<WaitForReady>().is_some();
+        let mut list = inner.subchannels.subscribe();
+
+        loop {
+            let subchannels = list.borrow_and_update().clone();
+            let mut receivers = subchannels.subscribe();
+            let states = current_states(&mut receivers);
+            for index in inner.balancer.connect(&states) {
+                subchannels.list[index].request_connect();
+            }
+
+            if let Some(index) = inner.balancer.pick(&states) {
+                match subchannels.list[index].reserve(false).await {
+                    Ok(reservation) => return reservation.send(request).await,
+                    // It left `Ready` since the pick; choose again.
+                    Err(Error::Status(_)) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            match subchannels.state(&states) {
+                ConnectivityState::Shutdown => return Err(Error::ConnectionClosed),
+                ConnectivityState::TransientFailure if !wait_for_ready => {
+                    return Err(match &subchannels.error {
+                        Some(message) => resolution_failed(message),
+                        None => Status::unavailable("connection is in transient failure"),
+                    }
+                    .into());
+                }
+                _ => {}
+            }
+
+            changed(&mut list, &mut receivers).await;
+        }
+    }
+}
+
+impl Inner {
+    /// Sets the request's scheme from the connector and, unless the call
+    /// chose its own, its authority.
+    fn set_origin(&self, request: &mut Request<Body>) {
+        let mut parts = request.uri().clone().into_parts();
+        parts.scheme = Some(self.scheme.clone());
+        if parts.authority.is_none() {
+            parts.authority = self.authority.clone();
+        }
+        if parts.path_and_query.is_none() {
+            parts.path_and_query = Some(PathAndQuery::from_static("/"));
+        }
+        // Without an authority the URI cannot carry a scheme either; h2
+        // then sends the path alone, as it always has.
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *request.uri_mut() = uri;
+        }
+    }
+
+    /// Brings the subchannel list in line with the latest resolution.
+    /// Subchannels for addresses that are still present are kept; removed
+    /// ones are shut down in the background.
+    fn update(&self, resolution: Resolution) {
+        let addrs = match resolution {
+            Resolution::Pending => return,
+            Resolution::Resolved(addrs) => addrs,
+            Resolution::Failed(message) => {
+                self.subchannels.send_if_modified(|subchannels| {
+                    if !subchannels.list.is_empty() {
+                        tracing::warn!(error = %message, "name resolution failed, keeping addresses");
+                        return false;
+                    }
+                    let changed = !subchannels.shutdown && subchannels.error.as_ref() != Some(&message);
+                    if changed {
+                        subchannels.error = Some(message);
+                    }
+                    changed
+                });
+                return;
+            }
+        };
+
+        self.subchannels.send_if_modified(|subchannels| {
+            if subchannels.shutdown {
+                return false;
+            }
+
+            let before: Vec<_> = subchannels
+                .list
+                .iter()
+                .map(|s| s.address().clone())
+                .collect();
+            let before_error = subchannels.error.take();
+            let mut previous = std::mem::take(&mut subchannels.list);
+            for address in addrs {
+                if subchannels.list.iter().any(|s| *s.address() == address) {
+                    continue;
+                }
+                let subchannel = match previous.iter().position(|s| *s.address() == address) {
+                    Some(index) => previous.remove(index),
+                    None => Subchannel::connect_lazy(address, &self.config),
+                };
+                subchannels.list.push(subchannel);
+            }
+            subchannels.error = subchannels
+                .list
+                .is_empty()
+                .then(|| "resolver returned no addresses".to_string());
+
+            let changed = subchannels.error != before_error
+                || !subchannels
+                    .list
+                    .iter()
+                    .map(Subchannel::address)
+                    .eq(before.iter());
+            for subchannel in previous {
+                tokio::spawn(async move { subchannel.shutdown().await });
+            }
+            changed
+        });
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(task) = lock(&self.resolution).take() {
+            task.abort();
+        }
+    }
+}
+
+impl Subchannels {
+    fn subscribe(&self) -> Vec<watch::Receiver<ConnectivityState>> {
+        self.list.iter().map(Subchannel::subscribe).collect()
+    }
+
+    fn state(&self, states: &[ConnectivityState]) -> ConnectivityState {
+        if self.shutdown {
+            ConnectivityState::Shutdown
+        } else if !states.is_empty() {
+            balance::aggregate(states)
+        } else if self.error.is_some() {
+            ConnectivityState::TransientFailure
+        } else {
+            // Still waiting for the first addresses.
+            ConnectivityState::Idle
+        }
+    }
+}
+
+/// Applies resolver updates until the channel is dropped. Resolvers that
+/// stop publishing keep their last result.
+async fn watch_resolution(
+    inner: Weak<Inner>,
+    mut latest: Vec<Resolution>,
+    resolutions: Vec<watch::Receiver<Resolution>>,
+) {
+    let mut live: Vec<_> = resolutions.into_iter().enumerate().collect();
+    while !live.is_empty() {
+        let changes = live
+            .iter_mut()
+            .map(|(_, receiver)| Box::pin(receiver.changed()));
+        let (result, position, _) = future::select_all(changes).await;
+        if result.is_err() {
+            live.remove(position);
+            continue;
+        }
+
+        let (index, receiver) = &mut live[position];
+        latest[*index] = receiver.borrow_and_update().clone();
+        match inner.upgrade() {
+            Some(inner) => inner.update(merge(&latest)),
+            None => return,
+        }
+    }
+}
+
+/// Combines the results of several resolvers: their addresses are pooled,
+/// and the channel only fails resolution when none of them has addresses.
+fn merge(resolutions: &[Resolution]) -> Resolution {
+    let mut addrs = Vec::new();
+    let mut resolved = false;
+    let mut failure = None;
+    for resolution in resolutions {
+        match resolution {
+            Resolution::Resolved(list) => {
+                resolved = true;
+                addrs.extend(list.iter().cloned());
+            }
+            Resolution::Failed(message) => failure = failure.or(Some(message)),
+            Resolution::Pending => {}
+        }
+    }
+
+    match failure {
+        Some(message) if addrs.is_empty() => Resolution::Failed(message.clone()),
+        _ if resolved => Resolution::Resolved(addrs),
+        _ => Resolution::Pending,
+    }
+}
+
+/// Validates `config` and wraps its connector in a [`ProxyConnector`] when
+/// `ClientConfig::proxy` applies to any of `targets`. The flag is set when
+/// it does, so that host names are left for the proxy to resolve.
+///
+/// A tunnel bypasses the connector, so a custom connector ignores the
+/// environment's proxy and cannot be combined with an explicit one.
+fn dial_config(targets: &[Target], config: &ClientConfig) -> Result<(ClientConfig, bool)> {
+    config.validate()?;
+
+    let mut config = config.clone();
+    let proxy = match (&config.proxy, &config.connector) {
+        (ProxyConfig::FromEnv, Some(_)) => None,
+        (ProxyConfig::Uri(_), Some(_)) => {
+            return Err(Error::Config(
+                "a proxy cannot be used with a custom connector".to_string(),
+            ))
+        }
+        (proxy, _) => proxy.proxy()?,
+    };
+    let proxy = proxy.filter(|proxy| proxy.applies_to(targets));
+    let proxied = proxy.is_some();
+    if let Some(proxy) = proxy {
+        let socket = DefaultConnector {
+            nodelay: config.tcp_nodelay,
+            keepalive: config.tcp_keepalive,
+        };
+        let connector = ProxyConnector::new(proxy, config.connector()).with_socket(socket);
+        config.connector = Some(Arc::new(connector));
+    }
+    Ok((config, proxied))
+}
+
+/// Refuses to dial: a channel built on a caller's stream has no way to open
+/// another.
+#[derive(Debug)]
+struct NoReconnect;
+
+impl Connector for NoReconnect {
+    fn connect(&self, _address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        Box::pin(future::ready(Err(Error::ConnectionClosed)))
+    }
+}
+
+/// Authority of channels whose target names no host.
+const LOCALHOST: &str = "localhost";
+
+/// The authority calls on `target` carry unless configured otherwise.
+/// Address lists use their first address; other endpoints that are not a
+/// valid authority are percent-encoded.
+fn default_authority(target: &Target) -> String {
+    match target.scheme() {
+        "unix" => LOCALHOST.to_string(),
+        "ipv4" | "ipv6" => match resolver::first_listed(target) {
+            Some(addr) => addr.to_string(),
+            None => percent_encode(target.endpoint()),
+        },
+        _ => percent_encode(target.endpoint()),
+    }
+}
+
+// Escapes the bytes an authority cannot hold, so `%` and the URI's
+// unreserved, sub-delimiter, `:`, `@`, `[` and `]` characters pass through.
+fn percent_encode(endpoint: &str) -> String {
+    let mut encoded = String::with_capacity(endpoint.len());
+    for byte in endpoint.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => encoded.push(byte as char),
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+'
+            | b',' | b';' | b'=' | b':' | b'@' | b'[' | b']' | b'%' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn no_addresses(target: &Target) -> Status {
+    Status::unavailable(format!("no addresses resolved for {}", target))
+}
+
+fn resolution_failed(message: &str) -> Status {
+    Status::unavailable(format!("name resolution failed: {}", message))
+}
+
+fn current_states(receivers: &mut [watch::Receiver<ConnectivityState>]) -> Vec<ConnectivityState> {
+    receivers
+        .iter_mut()
+        .map(|receiver| *receiver.borrow_and_update())
+        .collect()
+}
+
+/// Waits for the subchannel list or the state of any subchannel to change.
+async fn changed(
+    list: &mut watch::Receiver<Subchannels>,
+    receivers: &mut [watch::Receiver<ConnectivityState>],
+) {
+    let states = async {
+        if receivers.is_empty() {
+            future::pending::<()>().await;
+        }
+        let changes = receivers
+            .iter_mut()
+            .map(|receiver| Box::pin(receiver.changed()));
+        let _ = future::select_all(changes).await;
+    };
+
+    tokio::select! {
+        _ = list.changed() => {}
+        _ = states => {}
+    }
+}
+
+impl Service<Request<Body>> for Channel {
+    type Response = Response<Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Response<Body>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // Stream slots are claimed inside the call future so that clones
+        // handed out per call never hold a slot while sitting idle.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let channel = self.clone();
+        channel.inner.set_origin(&mut request);
+
+        Box::pin(async move {
+            // The body is one framed message; the limit is on the message.
+            if let Some(len) = request.body().size_hint() {
+                let len = len.saturating_sub(5);
+                if len > channel.inner.config.max_message_size {
+                    return Err(Error::MessageTooLarge(len));
+                }
+            }
+
+            match request.extensions().get::<CancellationToken>().cloned() {
+                Some(token) => tokio::select! {
+                    biased;
+                    _ = token.cancelled() => Err(cancel::cancelled()),
+                    response = channel.route(request) => response,
+                },
+                None => channel.route(request).await,
+            }
+        })
+    }
+}
@@ -0,0 +1,139 @@
+// src/channel/preface.rs
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::oneshot;
+
+/// HTTP/2 frame headers are nine bytes; the fourth is the frame type.
+const FRAME_HEADER_LEN: usize = 9;
+const SETTINGS: u8 = 0x4;
+
+/// Watches the first frame a server sends over `inner`. HTTP/2 requires it
+/// to be SETTINGS, which is the first sign the server speaks the protocol
+/// rather than merely accepting the connection.
+///
+/// Reads stop at the end of that frame's header, and the read after it
+/// yields once, so whoever drives the connection sees the result before h2
+/// processes anything the server sent next.
+pub(super) struct Preface<T> {
+    inner: T,
+    header: [u8; FRAME_HEADER_LEN],
+    header_len: usize,
+    // Told whether the first frame is SETTINGS once its header is read.
+    settings: Option<oneshot::Sender<bool>>,
+    yielded: bool,
+}
+
+impl<T> Preface<T> {
+    pub(super) fn new(inner: T) -> (Self, oneshot::Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let preface = Self {
+            inner,
+            header: [0; FRAME_HEADER_LEN],
+            header_len: 0,
+            settings: Some(sender),
+            yielded: false,
+        };
+        (preface, receiver)
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Preface<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if this.settings.is_none() {
+            if !this.yielded {
+                this.yielded = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+
+        let wanted = (FRAME_HEADER_LEN - this.header_len).min(buf.remaining());
+        let mut header = ReadBuf::new(&mut this.header[this.header_len..][..wanted]);
+        ready!(Pin::new(&mut this.inner).poll_read(cx, &mut header))?;
+        let read = header.filled().len();
+        buf.put_slice(header.filled());
+        this.header_len += read;
+
+        if this.header_len == FRAME_HEADER_LEN {
+            if let Some(settings) = this.settings.take() {
+                let _ = settings.send(this.header[3] == SETTINGS);
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Preface<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_settings_header_is_seen_across_reads() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let (mut preface, mut settings) = Preface::new(client);
+
+        server.write_all(&[0, 0, 0, SETTINGS]).await.unwrap();
+        let mut buf = [0; 4];
+        preface.read_exact(&mut buf).await.unwrap();
+        assert!(settings.try_recv().is_err());
+
+        server.write_all(&[0, 0, 0, 0, 0]).await.unwrap();
+        let mut buf = [0; 5];
+        preface.read_exact(&mut buf).await.unwrap();
+        assert_eq!(settings.await, Ok(true));
+    }
+
+    #[tokio::test]
+    async fn test_other_first_frame_is_reported() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let (mut preface, settings) = Preface::new(client);
+
+        // A DATA frame header.
+        server
+            .write_all(&[0, 0, 0, 0, 0, 0, 0, 0, 1])
+            .await
+            .unwrap();
+        let mut buf = [0; 9];
+        preface.read_exact(&mut buf).await.unwrap();
+        assert_eq!(settings.await, Ok(false));
+    }
+}
@@ -0,0 +1,449 @@
+// src/channel/resolver.rs
+use crate::{Error, Result};
+use http::Uri;
+use std::fmt::{self, Debug};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Port used by `dns:`, `ipv4:` and `ipv6:` targets that do not name one.
+pub const DEFAULT_PORT: u16 = 443;
+
+/// A parsed gRPC target name such as `dns:///example.com:50051`.
+///
+/// `http://` and `https://` URIs are accepted as well and resolve like `dns:`
+/// targets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Target {
+    scheme: String,
+    authority: Option<String>,
+    endpoint: String,
+}
+
+impl Target {
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The authority between `//` and the endpoint, if the target has one.
+    pub fn authority(&self) -> Option<&str> {
+        self.authority.as_deref()
+    }
+
+    /// What the resolver resolves: `host:port`, an address list or a path.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    fn from_str(target: &str) -> Result<Self> {
+        if target.starts_with("http://") || target.starts_with("https://") {
+            return parse_uri(target);
+        }
+
+        let invalid = || Error::Protocol(format!("Invalid target: {}", target));
+        let (scheme, rest) = target.split_once(':').ok_or_else(invalid)?;
+        let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+        if !valid_scheme {
+            return Err(invalid());
+        }
+
+        let (authority, endpoint) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let (authority, endpoint) = rest.split_once('/').ok_or_else(invalid)?;
+                (Some(authority.to_string()), endpoint)
+            }
+            None => (None, rest),
+        };
+        if endpoint.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            scheme: scheme.to_ascii_lowercase(),
+            authority,
+            endpoint: endpoint.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.authority {
+            Some(authority) => write!(f, "{}://{}/{}", self.scheme, authority, self.endpoint),
+            None => write!(f, "{}:{}", self.scheme, self.endpoint),
+        }
+    }
+}
+
+fn parse_uri(target: &str) -> Result<Target> {
+    let uri =
+        Uri::from_str(target).map_err(|_| Error::Protocol(format!("Invalid URI: {}", target)))?;
+    let scheme = uri.scheme_str().unwrap_or("http");
+    let host = uri
+        .host()
+        .ok_or_else(|| Error::Protocol("Missing host".to_string()))?;
+    let port = uri
+        .port_u16()
+        .unwrap_or(if scheme == "https" { 443 } else { 80 });
+
+    Ok(Target {
+        scheme: scheme.to_string(),
+        authority: uri.authority().map(|authority| authority.to_string()),
+        endpoint: format!("{}:{}", host, port),
+    })
+}
+
+/// A resolved address a subchannel can dial.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Address {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+    /// A `host:port` the connector looks up when it dials. Host name
+    /// targets resolve to this when a proxy applies, so that the proxy
+    /// resolves the name.
+    Host(String),
+    /// An address only a custom [`Connector`](crate::transport::Connector)
+    /// understands, such as the name of an in-memory server.
+    Named(String),
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(addr) => write!(f, "{}", addr),
+            Address::Unix(path) => write!(f, "unix:{}", path.display()),
+            Address::Host(endpoint) => f.write_str(endpoint),
+            Address::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+impl From<SocketAddr> for Address {
+    fn from(addr: SocketAddr) -> Self {
+        Address::Tcp(addr)
+    }
+}
+
+/// The latest result published by a resolver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The first result is not in yet.
+    Pending,
+    Resolved(Vec<Address>),
+    /// Resolution failed. A channel that already has addresses keeps them.
+    Failed(String),
+}
+
+/// Turns a target into addresses and keeps them up to date.
+///
+/// `resolve` returns a watch receiver; the resolver publishes a new
+/// [`Resolution`] whenever the addresses change. Background work should stop
+/// once every receiver is dropped, which happens when the channel goes away.
+pub trait Resolver: Debug + Send + Sync + 'static {
+    fn resolve(&self, target: &Target) -> watch::Receiver<Resolution>;
+}
+
+/// Resolves every target to a fixed address list that can be replaced at
+/// runtime with [`StaticResolver::update`]. Clones share the list.
+#[derive(Clone, Debug)]
+pub struct StaticResolver {
+    resolution: Arc<watch::Sender<Resolution>>,
+}
+
+impl StaticResolver {
+    pub fn new<I, A>(addrs: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<Address>,
+    {
+        let resolution = Resolution::Resolved(addrs.into_iter().map(Into::into).collect());
+        Self {
+            resolution: Arc::new(watch::Sender::new(resolution)),
+        }
+    }
+
+    /// Publishes a new address list to every channel using this resolver.
+    pub fn update<I, A>(&self, addrs: I)
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<Address>,
+    {
+        let resolution = Resolution::Resolved(addrs.into_iter().map(Into::into).collect());
+        self.resolution.send_replace(resolution);
+    }
+
+    /// Publishes a resolution failure.
+    pub fn fail(&self, message: impl Into<String>) {
+        self.resolution
+            .send_replace(Resolution::Failed(message.into()));
+    }
+}
+
+impl Resolver for StaticResolver {
+    fn resolve(&self, _target: &Target) -> watch::Receiver<Resolution> {
+        self.resolution.subscribe()
+    }
+}
+
+/// Resolves `host:port` with the system resolver and looks the name up
+/// again every `refresh_interval`.
+#[derive(Clone, Debug)]
+pub struct DnsResolver {
+    pub refresh_interval: Duration,
+}
+
+impl Default for DnsResolver {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Resolver for DnsResolver {
+    fn resolve(&self, target: &Target) -> watch::Receiver<Resolution> {
+        let (sender, receiver) = watch::channel(Resolution::Pending);
+        let endpoint = match split_host_port(target.endpoint()) {
+            Ok((host, port)) => format!("{}:{}", host, port.unwrap_or(DEFAULT_PORT)),
+            Err(e) => {
+                sender.send_replace(Resolution::Failed(e.to_string()));
+                return receiver;
+            }
+        };
+        let refresh_interval = self.refresh_interval;
+
+        tokio::spawn(async move {
+            loop {
+                let resolution = match tokio::net::lookup_host(&endpoint).await {
+                    Ok(addrs) => Resolution::Resolved(addrs.map(Address::Tcp).collect()),
+                    Err(e) => Resolution::Failed(format!("{}: {}", endpoint, e)),
+                };
+                sender.send_if_modified(|current| {
+                    let changed = *current != resolution;
+                    if changed {
+                        *current = resolution;
+                    }
+                    changed
+                });
+
+                tokio::select! {
+                    _ = tokio::time::sleep(refresh_interval) => {}
+                    _ = sender.closed() => return,
+                }
+            }
+        });
+        receiver
+    }
+}
+
+/// Picks the built-in resolver for `target`'s scheme. With `proxied` set,
+/// host names are left for the proxy to resolve instead of looked up here.
+pub(crate) fn builtin(target: &Target, proxied: bool) -> Result<Box<dyn Resolver>> {
+    let addrs = match target.scheme() {
+        "http" | "https" | "dns" => {
+            let (host, port) = split_host_port(target.endpoint())?;
+            match host.parse::<IpAddr>() {
+                Ok(ip) => vec![SocketAddr::new(ip, port.unwrap_or(DEFAULT_PORT)).into()],
+                Err(_) if proxied => vec![Address::Host(format!(
+                    "{}:{}",
+                    host,
+                    port.unwrap_or(DEFAULT_PORT)
+                ))],
+                Err(_) => return Ok(Box::new(DnsResolver::default())),
+            }
+        }
+        "ipv4" => parse_list(target.endpoint(), parse_ipv4)?,
+        "ipv6" => parse_list(target.endpoint(), parse_ipv6)?,
+        "unix" => {
+            // `unix:///tmp/sock` has an empty authority and an absolute path.
+            let path = match target.authority() {
+                Some("") => format!("/{}", target.endpoint()),
+                Some(_) => {
+                    return Err(Error::Protocol(format!(
+                        "unix: targets cannot have an authority: {}",
+                        target
+                    )))
+                }
+                None => target.endpoint().to_string(),
+            };
+            vec![Address::Unix(PathBuf::from(path))]
+        }
+        scheme => {
+            return Err(Error::Protocol(format!(
+                "Unsupported target scheme: {}",
+                scheme
+            )))
+        }
+    };
+
+    Ok(Box::new(StaticResolver::new(addrs)))
+}
+
+/// The first address of an `ipv4:` or `ipv6:` target's address list.
+pub(crate) fn first_listed(target: &Target) -> Option<SocketAddr> {
+    let first = target.endpoint().split(',').next()?;
+    match target.scheme() {
+        "ipv4" => parse_ipv4(first),
+        "ipv6" => parse_ipv6(first),
+        _ => None,
+    }
+}
+
+fn parse_list(endpoint: &str, parse: fn(&str) -> Option<SocketAddr>) -> Result<Vec<Address>> {
+    endpoint
+        .split(',')
+        .map(|addr| {
+            parse(addr)
+                .map(Address::Tcp)
+                .ok_or_else(|| Error::Protocol(format!("Invalid address: {}", addr)))
+        })
+        .collect()
+}
+
+fn parse_ipv4(addr: &str) -> Option<SocketAddr> {
+    addr.parse::<SocketAddrV4>()
+        .ok()
+        .or_else(|| {
+            let ip = addr.parse::<Ipv4Addr>().ok()?;
+            Some(SocketAddrV4::new(ip, DEFAULT_PORT))
+        })
+        .map(SocketAddr::V4)
+}
+
+fn parse_ipv6(addr: &str) -> Option<SocketAddr> {
+    addr.parse::<SocketAddrV6>()
+        .ok()
+        .or_else(|| {
+            let ip = addr
+                .strip_prefix('[')
+                .and_then(|addr| addr.strip_suffix(']'))
+                .unwrap_or(addr)
+                .parse::<Ipv6Addr>()
+                .ok()?;
+            Some(SocketAddrV6::new(ip, DEFAULT_PORT, 0, 0))
+        })
+        .map(SocketAddr::V6)
+}
+
+/// Splits `host`, `host:port`, `[v6]` or `[v6]:port`.
+fn split_host_port(endpoint: &str) -> Result<(&str, Option<u16>)> {
+    let invalid = || Error::Protocol(format!("Invalid host: {}", endpoint));
+
+    let (host, port) = match endpoint.strip_prefix('[') {
+        Some(rest) => {
+            let (host, rest) = rest.split_once(']').ok_or_else(invalid)?;
+            match rest {
+                "" => (host, None),
+                _ => (host, Some(rest.strip_prefix(':').ok_or_else(invalid)?)),
+            }
+        }
+        None => match endpoint.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (endpoint, None),
+        },
+    };
+    if host.is_empty() {
+        return Err(invalid());
+    }
+
+    let port = port
+        .map(|port| port.parse().map_err(|_| invalid()))
+        .transpose()?;
+    Ok((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(target: &str) -> Vec<Address> {
+        let target = target.parse().unwrap();
+        let resolution = builtin(&target, false)
+            .unwrap()
+            .resolve(&target)
+            .borrow()
+            .clone();
+        match resolution {
+            Resolution::Resolved(addrs) => addrs,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let target: Target = "dns:///example.com:8080".parse().unwrap();
+        assert_eq!(target.scheme(), "dns");
+        assert_eq!(target.authority(), Some(""));
+        assert_eq!(target.endpoint(), "example.com:8080");
+
+        let target: Target = "dns://8.8.8.8/example.com".parse().unwrap();
+        assert_eq!(target.authority(), Some("8.8.8.8"));
+        assert_eq!(target.endpoint(), "example.com");
+
+        let target: Target = "http://localhost".parse().unwrap();
+        assert_eq!(target.scheme(), "http");
+        assert_eq!(target.endpoint(), "localhost:80");
+
+        assert!("invalid-uri".parse::<Target>().is_err());
+        assert!("dns:".parse::<Target>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_static_targets() {
+        assert_eq!(
+            resolved("ipv4:127.0.0.1:50051,10.0.0.1"),
+            vec![
+                Address::Tcp("127.0.0.1:50051".parse().unwrap()),
+                Address::Tcp("10.0.0.1:443".parse().unwrap()),
+            ]
+        );
+        assert_eq!(
+            resolved("ipv6:[::1]:50051,::2"),
+            vec![
+                Address::Tcp("[::1]:50051".parse().unwrap()),
+                Address::Tcp("[::2]:443".parse().unwrap()),
+            ]
+        );
+        assert_eq!(
+            resolved("unix:///tmp/grpc.sock"),
+            vec![Address::Unix("/tmp/grpc.sock".into())]
+        );
+        assert_eq!(
+            resolved("unix:relative.sock"),
+            vec![Address::Unix("relative.sock".into())]
+        );
+        assert_eq!(
+            resolved("http://127.0.0.1:8080"),
+            vec![Address::Tcp("127.0.0.1:8080".parse().unwrap())]
+        );
+
+        for target in ["ipv4:::1", "ipv6:127.0.0.1", "unix://host/path", "foo:bar"] {
+            let target = target.parse().unwrap();
+            assert!(builtin(&target, false).is_err(), "accepted {}", target);
+        }
+    }
+
+    #[test]
+    fn test_proxied_host_names_are_not_resolved() {
+        let target = "dns:///grpc.internal:8443".parse().unwrap();
+        let resolution = builtin(&target, true)
+            .unwrap()
+            .resolve(&target)
+            .borrow()
+            .clone();
+        assert_eq!(
+            resolution,
+            Resolution::Resolved(vec![Address::Host("grpc.internal:8443".to_string())])
+        );
+    }
+}
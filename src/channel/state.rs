@@ -0,0 +1,24 @@
+// src/channel/state.rs
+use std::fmt;
+
+/// Connectivity state of a channel, as described by the gRPC connectivity
+/// semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectivityState {
+    /// No connection and no attempt in progress.
+    Idle,
+    /// A connection attempt is in progress.
+    Connecting,
+    /// The connection is established and accepting calls.
+    Ready,
+    /// The last connection attempt failed; waiting out the backoff delay.
+    TransientFailure,
+    /// The channel has been shut down and will not reconnect.
+    Shutdown,
+}
+
+impl fmt::Display for ConnectivityState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
@@ -0,0 +1,670 @@
+// src/channel/subchannel.rs
+use super::backoff::Backoff;
+use super::bdp::{Estimator, Received};
+use super::preface::Preface;
+use super::resolver::Address;
+use super::state::ConnectivityState;
+use crate::client::ClientConfig;
+use crate::transport::body::BodyGuard;
+use crate::transport::{http2, Body, BoxedIo};
+use crate::{Error, Result, Status};
+use bytes::Bytes;
+use futures_util::future::{self, BoxFuture};
+use h2::client::SendRequest;
+use h2::{Ping, PingPong, Reason};
+use http::{Request, Response};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::{watch, Mutex as AsyncMutex, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::{timeout, timeout_at, Instant};
+
+type H2Connection = h2::client::Connection<BoxedIo, Bytes>;
+
+/// A single HTTP/2 connection to one address.
+///
+/// A background task owns the connection. When it drops, the subchannel moves
+/// to `Connecting` and redials, backing off between failed attempts.
+///
+/// With `ClientConfig::idle_timeout` set, a connection that carries no calls
+/// for that long is closed and the subchannel returns to `Idle`; the next call
+/// dials again.
+///
+/// When the server sends GOAWAY, streams it already accepted run to
+/// completion on the old connection while new calls move to a fresh one.
+///
+/// With `ClientConfig::http2_adaptive_window` set, the connection's receive
+/// windows grow to the bandwidth-delay product measured with PINGs.
+#[derive(Clone, Debug)]
+pub(crate) struct Subchannel {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    shared: Arc<Shared>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    address: Address,
+    config: ClientConfig,
+    state: watch::Sender<ConnectivityState>,
+    send_request: Mutex<Option<Current>>,
+    generation: AtomicU64,
+    streams: Arc<Semaphore>,
+    // Wakes the background task when a call arrives on an idle subchannel.
+    connect: Notify,
+    // Tells the background task that the current connection got a GOAWAY.
+    going_away: Notify,
+    // Signalled whenever the last active call finishes.
+    drained: Notify,
+    active_calls: AtomicUsize,
+    last_active: Mutex<Instant>,
+    // Starts at the configured interval and doubles on `too_many_pings`.
+    keepalive_interval: Mutex<Duration>,
+    // DATA read by calls, sampled for the adaptive window.
+    received: Received,
+}
+
+/// The sender for the connection currently accepting new calls. The
+/// generation tells connections apart when reporting a GOAWAY.
+#[derive(Clone, Debug)]
+struct Current {
+    generation: u64,
+    send_request: SendRequest<Bytes>,
+}
+
+/// Why the background task stopped driving a connection.
+enum Disconnect {
+    Lost,
+    Idle,
+    GoingAway(Box<H2Connection>),
+}
+
+/// Whether the server closed the connection for pinging too often.
+///
+/// gRPC servers do so with a GOAWAY carrying ENHANCE_YOUR_CALM, which h2
+/// reports as a remote GOAWAY error with that reason. The `too_many_pings`
+/// debug data is not exposed by h2 0.3, so errors that arrive without the
+/// GOAWAY framing are matched on their message instead. That fallback relies
+/// on h2's `Display` output, which is not a stable API.
+fn is_too_many_pings(error: &h2::Error) -> bool {
+    if error.is_go_away() && error.is_remote() {
+        return error.reason() == Some(Reason::ENHANCE_YOUR_CALM);
+    }
+    error.to_string().contains("too_many_pings")
+}
+
+/// A stream slot on a ready connection, claimed before the request is sent.
+pub(crate) struct Reservation {
+    guard: CallGuard,
+    send_request: SendRequest<Bytes>,
+}
+
+/// Holds a stream slot and marks the call as active until its response body
+/// is dropped.
+struct CallGuard {
+    shared: Arc<Shared>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Subchannel {
+    /// Dials `address` and performs the HTTP/2 handshake. The first attempt
+    /// is made eagerly and its failure is returned to the caller; later
+    /// reconnects happen in the background.
+    pub(crate) async fn connect(address: Address, config: &ClientConfig) -> Result<Self> {
+        let shared = Shared::new(address, config, ConnectivityState::Connecting);
+        let connection = shared.dial().await?;
+        Ok(Self::spawn(shared, Some(connection)))
+    }
+
+    /// Runs the HTTP/2 handshake over an already open `stream`.
+    pub(crate) async fn from_stream(
+        address: Address,
+        stream: BoxedIo,
+        config: &ClientConfig,
+    ) -> Result<Self> {
+        let shared = Shared::new(address, config, ConnectivityState::Connecting);
+        let connection = shared.handshake(stream).await?;
+        Ok(Self::spawn(shared, Some(connection)))
+    }
+
+    /// Creates a subchannel in the `Idle` state without dialing.
+    pub(crate) fn connect_lazy(address: Address, config: &ClientConfig) -> Self {
+        let shared = Shared::new(address, config, ConnectivityState::Idle);
+        Self::spawn(shared, None)
+    }
+
+    fn spawn(shared: Arc<Shared>, connection: Option<H2Connection>) -> Self {
+        let task = tokio::spawn(shared.clone().run(connection));
+        Self {
+            inner: Arc::new(Inner {
+                shared,
+                task: Mutex::new(Some(task)),
+            }),
+        }
+    }
+
+    /// Shuts the subchannel down, giving calls in flight up to
+    /// `ClientConfig::drain_timeout` to finish.
+    pub(crate) async fn shutdown(&self) {
+        let shared = &self.inner.shared;
+        shared.set_state(ConnectivityState::Shutdown);
+        shared.set_send_request(None);
+
+        let deadline = Instant::now() + shared.config.drain_timeout;
+        if timeout_at(deadline, shared.drained()).await.is_err() {
+            tracing::warn!(addr = %shared.address, "drain timeout elapsed with calls in flight");
+        }
+
+        let task = lock(&self.inner.task).take();
+        if let Some(mut task) = task {
+            if timeout_at(deadline, &mut task).await.is_err() {
+                task.abort();
+            }
+        }
+    }
+
+    pub(crate) fn address(&self) -> &Address {
+        &self.inner.shared.address
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<ConnectivityState> {
+        self.inner.shared.state.subscribe()
+    }
+
+    /// Asks an idle subchannel to start connecting.
+    pub(crate) fn request_connect(&self) {
+        if *self.inner.shared.state.borrow() == ConnectivityState::Idle {
+            self.inner.shared.connect.notify_one();
+        }
+    }
+
+    /// Interval between keepalive PINGs, or `None` when keepalive is disabled.
+    pub(crate) fn keepalive_interval(&self) -> Option<Duration> {
+        let shared = &self.inner.shared;
+        shared
+            .config
+            .enable_http2_keepalive
+            .then(|| *lock(&shared.keepalive_interval))
+    }
+
+    /// Claims a stream slot and a ready connection for one call.
+    pub(crate) async fn reserve(&self, wait_for_ready: bool) -> Result<Reservation> {
+        let shared = &self.inner.shared;
+        let permit = shared
+            .streams
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+        let guard = CallGuard::new(shared.clone(), permit);
+
+        loop {
+            let Current {
+                generation,
+                send_request,
+            } = self.ready_send_request(wait_for_ready).await?;
+            match send_request.ready().await {
+                Ok(send_request) => {
+                    return Ok(Reservation {
+                        guard,
+                        send_request,
+                    })
+                }
+                // The request has not left the client yet, so it can move to
+                // the replacement connection.
+                Err(e) if e.is_go_away() && e.is_remote() => shared.go_away(generation),
+                Err(e) => return Err(Error::Transport(e)),
+            }
+        }
+    }
+
+    /// Waits for a usable connection. Fails fast with `Unavailable` while the
+    /// subchannel is in transient failure, unless `wait_for_ready` is set.
+    async fn ready_send_request(&self, wait_for_ready: bool) -> Result<Current> {
+        let shared = &self.inner.shared;
+        let mut state = shared.state.subscribe();
+        loop {
+            let current = *state.borrow_and_update();
+            match current {
+                ConnectivityState::Ready => {
+                    if let Some(current) = shared.current() {
+                        return Ok(current);
+                    }
+                }
+                ConnectivityState::TransientFailure if !wait_for_ready => {
+                    return Err(Status::unavailable("connection is in transient failure").into());
+                }
+                ConnectivityState::TransientFailure => {}
+                ConnectivityState::Shutdown => return Err(Error::ConnectionClosed),
+                ConnectivityState::Idle => shared.connect.notify_one(),
+                ConnectivityState::Connecting => {}
+            }
+            state.changed().await.map_err(|_| Error::ConnectionClosed)?;
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(task) = lock(&self.task).take() {
+            task.abort();
+        }
+        self.shared.set_state(ConnectivityState::Shutdown);
+    }
+}
+
+impl Shared {
+    fn new(address: Address, config: &ClientConfig, state: ConnectivityState) -> Arc<Self> {
+        Arc::new(Self {
+            address,
+            config: config.clone(),
+            state: watch::Sender::new(state),
+            send_request: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            streams: Arc::new(Semaphore::new(config.max_concurrent_streams as usize)),
+            connect: Notify::new(),
+            going_away: Notify::new(),
+            drained: Notify::new(),
+            active_calls: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+            keepalive_interval: Mutex::new(config.http2_keepalive_interval),
+            received: Received::default(),
+        })
+    }
+
+    fn current(&self) -> Option<Current> {
+        lock(&self.send_request).clone()
+    }
+
+    fn set_send_request(&self, send_request: Option<SendRequest<Bytes>>) {
+        *lock(&self.send_request) = send_request.map(|send_request| Current {
+            generation: self.generation.fetch_add(1, Ordering::SeqCst),
+            send_request,
+        });
+    }
+
+    fn set_state(&self, state: ConnectivityState) {
+        self.state.send_if_modified(|current| {
+            // Shutdown is terminal.
+            let changed = *current != state && *current != ConnectivityState::Shutdown;
+            if changed {
+                *current = state;
+            }
+            changed
+        });
+    }
+
+    fn is_shutdown(&self) -> bool {
+        *self.state.borrow() == ConnectivityState::Shutdown
+    }
+
+    async fn shutdown_requested(&self) {
+        let mut state = self.state.subscribe();
+        let _ = state
+            .wait_for(|state| *state == ConnectivityState::Shutdown)
+            .await;
+    }
+
+    /// Stops handing out the connection identified by `generation` after the
+    /// server announced it is going away.
+    fn go_away(&self, generation: u64) {
+        let mut current = lock(&self.send_request);
+        if current.as_ref().map(|current| current.generation) == Some(generation) {
+            *current = None;
+            self.going_away.notify_one();
+        }
+    }
+
+    /// Resolves once no calls are in flight.
+    async fn drained(&self) {
+        loop {
+            let notified = self.drained.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.active_calls.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Opens a stream with the configured connector, runs the HTTP/2
+    /// handshake and publishes the resulting sender, leaving the subchannel
+    /// `Ready`.
+    async fn dial(&self) -> Result<H2Connection> {
+        let connect = self.config.connector().connect(&self.address);
+        let stream = timeout(self.config.connect_timeout, connect).await??;
+        self.handshake(stream).await
+    }
+
+    /// Runs the HTTP/2 handshake over an established stream. The connection
+    /// counts as up once the server's SETTINGS arrive, within the connect
+    /// timeout.
+    async fn handshake(&self, stream: BoxedIo) -> Result<H2Connection> {
+        let config = &self.config;
+        config.validate()?;
+        let (stream, settings) = Preface::new(stream);
+
+        let mut builder = h2::client::Builder::new();
+        builder
+            .initial_window_size(config.http2_initial_stream_window_size)
+            .initial_connection_window_size(config.http2_initial_connection_window_size)
+            .max_frame_size(config.http2_max_frame_size)
+            .max_header_list_size(config.http2_max_header_list_size)
+            .header_table_size(config.http2_header_table_size)
+            .max_concurrent_streams(config.max_concurrent_streams);
+
+        let (send_request, mut connection) = builder
+            .handshake(Box::new(stream) as BoxedIo)
+            .await
+            .map_err(Error::Transport)?;
+
+        // The handshake only queues our own preface. A peer that accepts and
+        // then hangs up must fail the dial, not count as a connection.
+        // `Preface` pauses the connection once the server's first frame
+        // header arrives, so later frames are left for `drive`.
+        let preface = async {
+            tokio::select! {
+                biased;
+                settings = settings => match settings {
+                    Ok(true) => Ok(()),
+                    _ => Err(Error::Protocol("server did not start with SETTINGS".to_string())),
+                },
+                result = &mut connection => Err(match result {
+                    Ok(()) => Error::ConnectionClosed,
+                    Err(e) => Error::Transport(e),
+                }),
+            }
+        };
+        timeout(config.connect_timeout, preface).await??;
+
+        // A fresh connection starts its idle timer from now.
+        *lock(&self.last_active) = Instant::now();
+        self.set_send_request(Some(send_request));
+        self.set_state(ConnectivityState::Ready);
+        Ok(connection)
+    }
+
+    /// Drives the current connection and redials whenever it is lost. While
+    /// idle, waits for a call to ask for a connection before dialing.
+    /// Connections that received GOAWAY keep running alongside until their
+    /// remaining streams finish.
+    async fn run(self: Arc<Self>, mut connection: Option<H2Connection>) {
+        let mut backoff = Backoff::new(self.config.connection_backoff.clone());
+        let mut draining = JoinSet::new();
+
+        loop {
+            while draining.try_join_next().is_some() {}
+            if self.is_shutdown() {
+                break;
+            }
+
+            let current = match connection.take() {
+                Some(current) => current,
+                None => {
+                    if *self.state.borrow() == ConnectivityState::Idle {
+                        tokio::select! {
+                            _ = self.connect.notified() => {}
+                            _ = self.shutdown_requested() => break,
+                        }
+                    }
+                    self.set_state(ConnectivityState::Connecting);
+                    match self.dial().await {
+                        Ok(current) => current,
+                        Err(e) => {
+                            tracing::debug!(addr = %self.address, error = %e, "connect failed");
+                            self.set_state(ConnectivityState::TransientFailure);
+                            tokio::select! {
+                                _ = tokio::time::sleep(backoff.next_delay()) => {}
+                                _ = self.shutdown_requested() => break,
+                            }
+                            continue;
+                        }
+                    }
+                }
+            };
+            backoff.reset();
+
+            match self.drive(current).await {
+                Disconnect::Lost => self.set_send_request(None),
+                Disconnect::Idle => self.set_state(ConnectivityState::Idle),
+                Disconnect::GoingAway(connection) => {
+                    tracing::debug!(addr = %self.address, "connection going away, reconnecting");
+                    draining.spawn(async move {
+                        let _ = (*connection).await;
+                    });
+                }
+            }
+        }
+
+        // Let connections that are still finishing streams close on their own.
+        while draining.join_next().await.is_some() {}
+    }
+
+    async fn drive(&self, mut connection: H2Connection) -> Disconnect {
+        // Keepalive and the window estimator take turns with the one
+        // `PingPong` a connection hands out.
+        let ping_pong = connection.ping_pong().map(AsyncMutex::new);
+        let keepalive = self.keepalive(ping_pong.as_ref());
+        tokio::pin!(keepalive);
+        let (window, mut window_changed) = watch::channel(0);
+        let adaptive_window = self.adaptive_window(ping_pong.as_ref(), &window);
+        tokio::pin!(adaptive_window);
+
+        loop {
+            tokio::select! {
+                result = &mut connection => {
+                    if let Err(e) = result {
+                        self.connection_error(&e);
+                    }
+                    return Disconnect::Lost;
+                }
+                _ = &mut keepalive => return Disconnect::Lost,
+                _ = &mut adaptive_window => {}
+                Ok(()) = window_changed.changed() => {
+                    let size = *window_changed.borrow_and_update();
+                    self.resize_window(&mut connection, size);
+                }
+                _ = self.going_away.notified() => {
+                    // Ignore wakeups meant for an earlier connection.
+                    if self.current().is_none() {
+                        break;
+                    }
+                }
+                _ = self.idle() => {
+                    // Calls register as active before reading the sender, so
+                    // checking both under the lock cannot strand a new call.
+                    let mut send_request = lock(&self.send_request);
+                    if self.active_calls.load(Ordering::SeqCst) == 0 {
+                        *send_request = None;
+                        tracing::debug!(addr = %self.address, "closing idle connection");
+                        return Disconnect::Idle;
+                    }
+                }
+            }
+        }
+
+        Disconnect::GoingAway(Box::new(connection))
+    }
+
+    fn connection_error(&self, error: &h2::Error) {
+        tracing::debug!(addr = %self.address, error = %error, "connection error");
+
+        // Back off the interval so the next connection stays within the
+        // server's ping policy.
+        if is_too_many_pings(error) {
+            let mut interval = lock(&self.keepalive_interval);
+            *interval = interval.saturating_mul(2);
+            tracing::warn!(
+                addr = %self.address,
+                interval = ?*interval,
+                "server sent too_many_pings, increasing keepalive interval"
+            );
+        }
+    }
+
+    /// Sends a PING every keepalive interval and resolves if one is not
+    /// acknowledged within the keepalive timeout. Never resolves when
+    /// keepalive is disabled.
+    async fn keepalive(&self, ping_pong: Option<&AsyncMutex<PingPong>>) {
+        let ping_pong = match ping_pong {
+            Some(ping_pong) if self.config.enable_http2_keepalive => ping_pong,
+            _ => return future::pending().await,
+        };
+
+        loop {
+            let interval = *lock(&self.keepalive_interval);
+            tokio::time::sleep(interval).await;
+
+            if !self.config.http2_keepalive_while_idle
+                && self.active_calls.load(Ordering::SeqCst) == 0
+            {
+                continue;
+            }
+
+            let timeout = self.config.http2_keepalive_timeout;
+            let ping = async { ping_pong.lock().await.ping(Ping::opaque()).await };
+            match tokio::time::timeout(timeout, ping).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => {
+                    tracing::debug!(addr = %self.address, error = %e, "keepalive ping failed");
+                    return;
+                }
+                Err(_) => {
+                    tracing::debug!(addr = %self.address, "keepalive ping timed out");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Estimates the bandwidth-delay product from PING round trips while
+    /// data flows and publishes each larger window through `window`. Never
+    /// resolves while the estimate can still grow, or when the adaptive
+    /// window is disabled.
+    async fn adaptive_window(
+        &self,
+        ping_pong: Option<&AsyncMutex<PingPong>>,
+        window: &watch::Sender<u32>,
+    ) {
+        let ping_pong = match ping_pong {
+            Some(ping_pong) if self.config.http2_adaptive_window => ping_pong,
+            _ => return future::pending().await,
+        };
+
+        let mut estimator = Estimator::new(self.config.http2_initial_stream_window_size);
+        let mut start = self.received.total();
+        loop {
+            self.received.changed().await;
+
+            let mut ping_pong = ping_pong.lock().await;
+            let sent = Instant::now();
+            if ping_pong.ping(Ping::opaque()).await.is_err() {
+                // The connection is going down; its own error is reported.
+                return future::pending().await;
+            }
+            drop(ping_pong);
+            let rtt = sent.elapsed();
+
+            let total = self.received.total();
+            if let Some(size) = estimator.sample(total - start, rtt) {
+                window.send_replace(size);
+            }
+            start = total;
+        }
+    }
+
+    /// Grows the stream and connection receive windows to `size`, never
+    /// shrinking them below the configured sizes.
+    fn resize_window(&self, connection: &mut H2Connection, size: u32) {
+        let stream_window = size.max(self.config.http2_initial_stream_window_size);
+        let connection_window = size.max(self.config.http2_initial_connection_window_size);
+        tracing::debug!(
+            addr = %self.address,
+            stream_window,
+            connection_window,
+            "growing flow-control windows"
+        );
+        connection.set_target_window_size(connection_window);
+        if let Err(e) = connection.set_initial_window_size(stream_window) {
+            tracing::debug!(addr = %self.address, error = %e, "failed to grow stream window");
+        }
+    }
+
+    /// Resolves once the connection has been unused for the idle timeout.
+    /// Never resolves when no idle timeout is configured.
+    async fn idle(&self) {
+        match self.config.idle_timeout {
+            Some(idle_timeout) => self.idle_for(idle_timeout).await,
+            None => future::pending().await,
+        }
+    }
+
+    /// Resolves once no call has been active for `idle_timeout`.
+    async fn idle_for(&self, idle_timeout: Duration) {
+        loop {
+            let idle_since = *lock(&self.last_active);
+            if self.active_calls.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(idle_timeout).await;
+            } else if idle_since.elapsed() >= idle_timeout {
+                return;
+            } else {
+                tokio::time::sleep_until(idle_since + idle_timeout).await;
+            }
+        }
+    }
+}
+
+impl CallGuard {
+    fn new(shared: Arc<Shared>, permit: OwnedSemaphorePermit) -> Self {
+        shared.active_calls.fetch_add(1, Ordering::SeqCst);
+        Self {
+            shared,
+            _permit: permit,
+        }
+    }
+}
+
+impl BodyGuard for CallGuard {
+    fn on_data(&self, len: usize) {
+        if self.shared.config.http2_adaptive_window {
+            self.shared.received.record(len);
+        }
+    }
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        *lock(&self.shared.last_active) = Instant::now();
+        if self.shared.active_calls.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.drained.notify_waiters();
+        }
+    }
+}
+
+pub(super) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Reservation {
+    /// Sends `request` on the reserved stream. The slot is released once the
+    /// response body is dropped.
+    pub(crate) fn send(
+        mut self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>>> {
+        let guard = self.guard;
+        let sent = http2::send(&mut self.send_request, request);
+        Box::pin(async move {
+            let response = sent.await?;
+            Ok(response.map(|body| body.with_guard(guard)))
+        })
+    }
+}
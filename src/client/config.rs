@@ -0,0 +1,386 @@
+// src/client/config.rs
+use super::hedging::HedgingPolicy;
+use super::retry::RetryPolicy;
+use super::service_config::ServiceConfig;
+use super::throttle::RetryThrottling;
+use super::ClientConfig;
+use crate::channel::{BackoffConfig, LoadBalancingPolicy};
+use crate::transport::{Connector, ProxyConfig};
+use crate::{Error, Result};
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Prefix of the environment variables read by [`ClientConfigBuilder::env`].
+pub const ENV_PREFIX: &str = "GRPC_CLIENT_";
+
+/// Builds a [`ClientConfig`], checking the result with
+/// [`ClientConfig::validate`] on [`build`](Self::build).
+///
+/// Besides the setters, settings can be read from a TOML or JSON file and
+/// from environment variables. Each source overrides the settings applied
+/// before it, so a file of defaults can be tuned per deployment:
+///
+/// ```no_run
+/// # use grpc_project::client::ClientConfig;
+/// # fn main() -> grpc_project::Result<()> {
+/// let config = ClientConfig::builder()
+///     .file("client.toml")?
+///     .env()?
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Keys are the `ClientConfig` field names. Nested tables are joined with
+/// `_`, so `[connection_backoff] base_delay` in a file is
+/// `GRPC_CLIENT_CONNECTION_BACKOFF_BASE_DELAY` in the environment.
+/// Durations are written with a unit (`250ms`, `30s`, `5m`, `1h`), optional
+/// settings are cleared with `none`, and `service_config` takes a gRPC
+/// service config as a table or JSON string. Unknown keys are rejected.
+///
+/// Setting any key of `retry_policy` or `retry_throttling` starts from that
+/// type's defaults, and `retry_policy_retryable_status_codes` is a
+/// comma-separated list of status names such as `UNAVAILABLE`. Hedging
+/// policies are per method, so they have no keys of their own; set them
+/// with a `hedgingPolicy` in `service_config`. The connector can only be set
+/// in code.
+#[derive(Clone, Debug, Default)]
+pub struct ClientConfigBuilder {
+    config: ClientConfig,
+}
+
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+macro_rules! optional_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = Some($field);
+                self
+            }
+        )*
+    };
+}
+
+impl ClientConfigBuilder {
+    /// Starts from `config` instead of the defaults.
+    pub fn from_config(config: ClientConfig) -> Self {
+        Self { config }
+    }
+
+    setters! {
+        max_message_size: usize,
+        max_concurrent_streams: u32,
+        enable_http2_keepalive: bool,
+        http2_keepalive_interval: Duration,
+        http2_keepalive_timeout: Duration,
+        http2_keepalive_while_idle: bool,
+        http2_initial_stream_window_size: u32,
+        http2_initial_connection_window_size: u32,
+        http2_max_frame_size: u32,
+        http2_max_header_list_size: u32,
+        http2_header_table_size: u32,
+        http2_adaptive_window: bool,
+        tcp_nodelay: bool,
+        connect_timeout: Duration,
+        connection_backoff: BackoffConfig,
+        drain_timeout: Duration,
+        load_balancing_policy: LoadBalancingPolicy,
+        proxy: ProxyConfig,
+        allow_insecure_credentials: bool,
+    }
+
+    optional_setters! {
+        tcp_keepalive: Duration,
+        idle_timeout: Duration,
+        authority: String,
+        user_agent: String,
+        timeout: Duration,
+        retry_policy: RetryPolicy,
+        retry_throttling: RetryThrottling,
+        service_config: ServiceConfig,
+    }
+
+    pub fn connector<C: Connector>(mut self, connector: C) -> Self {
+        self.config.connector = Some(Arc::new(connector));
+        self
+    }
+
+    /// Hedges calls to `method`, a full path such as
+    /// `/package.Service/Method`.
+    pub fn hedging_policy(mut self, method: impl Into<String>, policy: HedgingPolicy) -> Self {
+        self.config.hedging_policies.insert(method.into(), policy);
+        self
+    }
+
+    /// Applies the settings in a `.toml` or `.json` file.
+    pub fn file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read client config {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => self.toml(&source),
+            Some("json") => self.json(&source),
+            _ => Err(Error::Config(format!(
+                "Unsupported client config file {}: expected .toml or .json",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Applies the settings in a TOML document.
+    pub fn toml(self, source: &str) -> Result<Self> {
+        let table: Map<String, Value> = toml::from_str(source)
+            .map_err(|e| Error::Config(format!("Invalid TOML client config: {}", e)))?;
+        self.table(table)
+    }
+
+    /// Applies the settings in a JSON object.
+    pub fn json(self, source: &str) -> Result<Self> {
+        let table: Map<String, Value> = serde_json::from_str(source)
+            .map_err(|e| Error::Config(format!("Invalid JSON client config: {}", e)))?;
+        self.table(table)
+    }
+
+    /// Applies the `GRPC_CLIENT_*` environment variables.
+    pub fn env(self) -> Result<Self> {
+        self.env_prefixed(ENV_PREFIX)
+    }
+
+    /// Applies the environment variables starting with `prefix`, e.g.
+    /// `PREFIX_CONNECT_TIMEOUT` for `connect_timeout`. Other variables are
+    /// ignored even if they are not valid Unicode.
+    pub fn env_prefixed(mut self, prefix: &str) -> Result<Self> {
+        let mut vars = Vec::new();
+        for (name, value) in std::env::vars_os() {
+            let Some(key) = name.to_str().and_then(|name| name.strip_prefix(prefix)) else {
+                continue;
+            };
+            let value = value.into_string().map_err(|_| {
+                Error::Config(format!("{} is not valid Unicode", name.to_string_lossy()))
+            })?;
+            vars.push((key.to_ascii_lowercase(), value));
+        }
+        // Apply in a stable order so errors do not depend on the platform.
+        vars.sort();
+        for (key, value) in vars {
+            self.set(&key, &value)?;
+        }
+        Ok(self)
+    }
+
+    /// Checks the settings and returns the config.
+    pub fn build(self) -> Result<ClientConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    fn table(mut self, table: Map<String, Value>) -> Result<Self> {
+        self.apply_table("", table)?;
+        Ok(self)
+    }
+
+    fn apply_table(&mut self, prefix: &str, table: Map<String, Value>) -> Result<()> {
+        for (key, value) in table {
+            let key = format!("{}{}", prefix, key);
+            match value {
+                // A service config is handed over whole, as JSON.
+                Value::Object(_) if key == "service_config" => {
+                    self.set(&key, &value.to_string())?
+                }
+                Value::Object(table) => self.apply_table(&format!("{}_", key), table)?,
+                Value::String(value) => self.set(&key, &value)?,
+                Value::Bool(_) | Value::Number(_) => self.set(&key, &value.to_string())?,
+                Value::Null | Value::Array(_) => return Err(invalid(&key, &value.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies one setting given as text, as it appears in a file or
+    /// environment variable.
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let config = &mut self.config;
+        match key {
+            "max_message_size" => config.max_message_size = parse(key, value)?,
+            "max_concurrent_streams" => config.max_concurrent_streams = parse(key, value)?,
+            "enable_http2_keepalive" => config.enable_http2_keepalive = parse(key, value)?,
+            "http2_keepalive_interval" => {
+                config.http2_keepalive_interval = parse_duration(key, value)?
+            }
+            "http2_keepalive_timeout" => {
+                config.http2_keepalive_timeout = parse_duration(key, value)?
+            }
+            "http2_keepalive_while_idle" => config.http2_keepalive_while_idle = parse(key, value)?,
+            "http2_initial_stream_window_size" => {
+                config.http2_initial_stream_window_size = parse(key, value)?
+            }
+            "http2_initial_connection_window_size" => {
+                config.http2_initial_connection_window_size = parse(key, value)?
+            }
+            "http2_max_frame_size" => config.http2_max_frame_size = parse(key, value)?,
+            "http2_max_header_list_size" => config.http2_max_header_list_size = parse(key, value)?,
+            "http2_header_table_size" => config.http2_header_table_size = parse(key, value)?,
+            "http2_adaptive_window" => config.http2_adaptive_window = parse(key, value)?,
+            "tcp_nodelay" => config.tcp_nodelay = parse(key, value)?,
+            "tcp_keepalive" => config.tcp_keepalive = parse_optional_duration(key, value)?,
+            "connect_timeout" => config.connect_timeout = parse_duration(key, value)?,
+            "connection_backoff_base_delay" => {
+                config.connection_backoff.base_delay = parse_duration(key, value)?
+            }
+            "connection_backoff_multiplier" => {
+                config.connection_backoff.multiplier = parse(key, value)?
+            }
+            "connection_backoff_jitter" => config.connection_backoff.jitter = parse(key, value)?,
+            "connection_backoff_max_delay" => {
+                config.connection_backoff.max_delay = parse_duration(key, value)?
+            }
+            "idle_timeout" => config.idle_timeout = parse_optional_duration(key, value)?,
+            "drain_timeout" => config.drain_timeout = parse_duration(key, value)?,
+            "load_balancing_policy" => {
+                config.load_balancing_policy = match value {
+                    "pick_first" => LoadBalancingPolicy::PickFirst,
+                    "round_robin" => LoadBalancingPolicy::RoundRobin,
+                    _ => return Err(invalid(key, value)),
+                }
+            }
+            "proxy" => {
+                config.proxy = match value {
+                    "env" => ProxyConfig::FromEnv,
+                    "none" => ProxyConfig::Disabled,
+                    uri => ProxyConfig::Uri(uri.to_string()),
+                }
+            }
+            "authority" => config.authority = parse_optional_string(value),
+            "user_agent" => config.user_agent = parse_optional_string(value),
+            "allow_insecure_credentials" => config.allow_insecure_credentials = parse(key, value)?,
+            "timeout" => config.timeout = parse_optional_duration(key, value)?,
+            "retry_policy" if value.trim() == "none" => config.retry_policy = None,
+            "retry_policy_max_attempts" => retry_policy(config).max_attempts = parse(key, value)?,
+            "retry_policy_initial_backoff" => {
+                retry_policy(config).initial_backoff = parse_duration(key, value)?
+            }
+            "retry_policy_max_backoff" => {
+                retry_policy(config).max_backoff = parse_duration(key, value)?
+            }
+            "retry_policy_backoff_multiplier" => {
+                retry_policy(config).backoff_multiplier = parse(key, value)?
+            }
+            "retry_policy_retryable_status_codes" => {
+                retry_policy(config).retryable_status_codes = value
+                    .split(',')
+                    .map(|code| code.trim().parse().map_err(|_| invalid(key, value)))
+                    .collect::<Result<_>>()?
+            }
+            "retry_throttling" if value.trim() == "none" => config.retry_throttling = None,
+            "retry_throttling_max_tokens" => {
+                retry_throttling(config).max_tokens = parse(key, value)?
+            }
+            "retry_throttling_token_ratio" => {
+                retry_throttling(config).token_ratio = parse(key, value)?
+            }
+            "service_config" => {
+                config.service_config = match value {
+                    "none" => None,
+                    json => Some(ServiceConfig::from_json(json)?),
+                }
+            }
+            _ => return Err(Error::Config(format!("Unknown client config key: {}", key))),
+        }
+        Ok(())
+    }
+}
+
+impl ClientConfig {
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+}
+
+fn invalid(key: &str, value: &str) -> Error {
+    Error::Config(format!(
+        "Invalid value for client config key {}: {}",
+        key, value
+    ))
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| invalid(key, value))
+}
+
+/// Parses a duration with a unit, such as `250ms`, `1.5s`, `5m` or `1h`.
+fn parse_duration(key: &str, value: &str) -> Result<Duration> {
+    let trimmed = value.trim();
+    let split = trimmed
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| invalid(key, value))?;
+    let (amount, unit) = trimmed.split_at(split);
+    let scale = match unit {
+        "ms" => 0.001,
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(invalid(key, value)),
+    };
+    let amount: f64 = amount.parse().map_err(|_| invalid(key, value))?;
+    Duration::try_from_secs_f64(amount * scale).map_err(|_| invalid(key, value))
+}
+
+fn parse_optional_string(value: &str) -> Option<String> {
+    match value.trim() {
+        "none" => None,
+        _ => Some(value.to_string()),
+    }
+}
+
+fn retry_policy(config: &mut ClientConfig) -> &mut RetryPolicy {
+    config.retry_policy.get_or_insert_with(RetryPolicy::default)
+}
+
+fn retry_throttling(config: &mut ClientConfig) -> &mut RetryThrottling {
+    config
+        .retry_throttling
+        .get_or_insert_with(RetryThrottling::default)
+}
+
+fn parse_optional_duration(key: &str, value: &str) -> Result<Option<Duration>> {
+    match value.trim() {
+        "none" => Ok(None),
+        _ => parse_duration(key, value).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let parse = |value| parse_duration("key", value);
+        assert_eq!(parse("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse("1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse("1h").unwrap(), Duration::from_secs(3600));
+
+        for value in ["30", "s", "-1s", "1d", "1.2.3s"] {
+            assert!(parse(value).is_err(), "accepted {}", value);
+        }
+    }
+}
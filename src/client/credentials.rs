@@ -0,0 +1,196 @@
+// src/client/credentials.rs
+use crate::{Metadata, Result};
+use futures_util::future::BoxFuture;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// How long before expiry a [`RefreshingToken`] fetches a new token.
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(10);
+
+/// Produces metadata attached to calls, such as an `authorization` header.
+///
+/// Credentials are asked for metadata once per call, before the first
+/// attempt; the entries override call metadata with the same key. A failure
+/// ends the call with `Unavailable`, unless it already is a status.
+///
+/// Credentials are only sent over connections whose connector reports them
+/// secure, unless `ClientConfig::allow_insecure_credentials` is set; calls
+/// on other connections fail with `Unauthenticated`.
+pub trait CallCredentials: Debug + Send + Sync + 'static {
+    /// Metadata for a call to `method`, a full path such as
+    /// `/package.Service/Method`.
+    fn metadata<'a>(&'a self, method: &'a str) -> BoxFuture<'a, Result<Metadata>>;
+}
+
+// Lets one set of credentials, and its cached token, serve many calls.
+impl<C: CallCredentials + ?Sized> CallCredentials for Arc<C> {
+    fn metadata<'a>(&'a self, method: &'a str) -> BoxFuture<'a, Result<Metadata>> {
+        (**self).metadata(method)
+    }
+}
+
+/// Sends the same bearer token with every call.
+#[derive(Clone)]
+pub struct StaticToken {
+    token: String,
+}
+
+impl StaticToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+// The token is a secret, so it stays out of logs.
+impl Debug for StaticToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticToken").finish_non_exhaustive()
+    }
+}
+
+impl CallCredentials for StaticToken {
+    fn metadata<'a>(&'a self, _method: &'a str) -> BoxFuture<'a, Result<Metadata>> {
+        Box::pin(async move { bearer(&self.token) })
+    }
+}
+
+/// An access token and the time it stops being valid.
+#[derive(Clone)]
+pub struct Token {
+    pub value: String,
+    /// `None` for tokens that never expire.
+    pub expires_at: Option<Instant>,
+}
+
+impl Token {
+    /// A token valid for `lifetime` from now, as in an OAuth `expires_in`.
+    pub fn expiring_in(value: impl Into<String>, lifetime: Duration) -> Self {
+        Self {
+            value: value.into(),
+            expires_at: Some(Instant::now() + lifetime),
+        }
+    }
+
+    fn is_fresh(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| Instant::now() + margin < expires_at)
+    }
+}
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+type Fetch = Box<dyn Fn() -> BoxFuture<'static, Result<Token>> + Send + Sync>;
+
+/// Sends a bearer token fetched on demand, such as a short-lived OAuth or
+/// JWT token, and caches it until shortly before it expires.
+///
+/// Calls that find the token stale wait for a single fetch between them.
+/// A failed fetch fails the calls waiting on it; the next call tries again.
+pub struct RefreshingToken {
+    fetch: Fetch,
+    refresh_margin: Duration,
+    cached: Mutex<Option<Token>>,
+}
+
+impl RefreshingToken {
+    pub fn new<F, Fut>(fetch: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Token>> + Send + 'static,
+    {
+        Self {
+            fetch: Box::new(move || Box::pin(fetch())),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Fetches a new token once the cached one has less than `margin` left.
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    async fn token(&self) -> Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref().filter(|t| t.is_fresh(self.refresh_margin)) {
+            return Ok(token.value.clone());
+        }
+        let token = (self.fetch)().await?;
+        let value = token.value.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+}
+
+impl Debug for RefreshingToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshingToken")
+            .field("refresh_margin", &self.refresh_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CallCredentials for RefreshingToken {
+    fn metadata<'a>(&'a self, _method: &'a str) -> BoxFuture<'a, Result<Metadata>> {
+        Box::pin(async move { bearer(&self.token().await?) })
+    }
+}
+
+fn bearer(token: &str) -> Result<Metadata> {
+    let mut metadata = Metadata::new();
+    metadata.insert("authorization", &format!("Bearer {}", token))?;
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting(lifetime: Duration) -> (RefreshingToken, Arc<AtomicUsize>) {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let credentials = RefreshingToken::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { Ok(Token::expiring_in(format!("token-{}", n), lifetime)) }
+        });
+        (credentials, fetches)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_is_cached_until_refresh_margin() {
+        let (credentials, fetches) = counting(Duration::from_secs(60));
+
+        assert_eq!(credentials.token().await.unwrap(), "token-1");
+        tokio::time::advance(Duration::from_secs(45)).await;
+        assert_eq!(credentials.token().await.unwrap(), "token-1");
+
+        // Within the 10s margin of expiry the token is replaced.
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert_eq!(credentials.token().await.unwrap(), "token-2");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_fetch() {
+        let (credentials, fetches) = counting(Duration::from_secs(60));
+        let tokens = futures_util::future::join_all((0..5).map(|_| credentials.token())).await;
+        assert!(tokens
+            .iter()
+            .all(|token| token.as_deref().unwrap() == "token-1"));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,50 @@
+// src/client/hedging.rs
+use super::retry::{self, MAX_ATTEMPTS_LIMIT};
+use crate::status::Code;
+use crate::{Error, Result};
+use std::time::Duration;
+
+/// Hedging policy for unary calls, following the gRPC retry design: the
+/// request is sent again every `hedging_delay` until a response arrives, and
+/// the first successful response wins.
+#[derive(Clone, Debug)]
+pub struct HedgingPolicy {
+    /// Total attempts including the original call; capped at
+    /// [`MAX_ATTEMPTS_LIMIT`].
+    pub max_attempts: u32,
+    pub hedging_delay: Duration,
+    /// Statuses that do not end the call; any other failure is returned
+    /// immediately and cancels the outstanding attempts.
+    pub non_fatal_status_codes: Vec<Code>,
+}
+
+impl Default for HedgingPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 2,
+            hedging_delay: Duration::from_millis(100),
+            non_fatal_status_codes: Vec::new(),
+        }
+    }
+}
+
+impl HedgingPolicy {
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts.clamp(1, MAX_ATTEMPTS_LIMIT)
+    }
+
+    /// Rejects policies that would never send a hedge. A zero
+    /// `hedging_delay` is allowed: it sends every attempt at once.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_attempts < 2 {
+            return Err(Error::Config(
+                "hedging policy max_attempts must be at least 2".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_non_fatal(&self, error: &Error) -> bool {
+        retry::status_code(error).is_some_and(|code| self.non_fatal_status_codes.contains(&code))
+    }
+}
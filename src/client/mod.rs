@@ -1,30 +1,137 @@
 // src/client/mod.rs
-use crate::transport::{Connection, Http2Transport};
-use crate::{Error, Metadata, Result};
-use bytes::Bytes;
-use http::{Request, Response, Uri};
-use std::convert::TryInto;
-use std::str::FromStr;
+pub mod config;
+pub mod credentials;
+pub mod hedging;
+pub mod options;
+pub mod retry;
+pub mod service_config;
+pub mod streaming;
+pub mod throttle;
+
+pub use config::ClientConfigBuilder;
+pub use credentials::{CallCredentials, RefreshingToken, StaticToken, Token};
+pub use hedging::HedgingPolicy;
+pub use options::CallOptions;
+pub use retry::RetryPolicy;
+pub use service_config::{MethodConfig, ServiceConfig};
+pub use streaming::Streaming;
+pub use throttle::RetryThrottling;
+
+use crate::channel::{BackoffConfig, Channel, LoadBalancingPolicy, WaitForReady};
+use crate::codec::{CompressionEncoding, Frame, GrpcCodec};
+use crate::status::{Code, Status};
+use crate::transport::cancel;
+use crate::transport::{
+    Body, CancellationToken, Connector, DefaultConnector, InProcessConnector, Io, ProxyConfig,
+};
+use crate::{Error, Metadata, Result, DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_WINDOW_SIZE};
+use bytes::{Bytes, BytesMut};
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use http::uri::{Authority, Scheme};
+use http::{HeaderMap, HeaderValue, Request, Response, Uri};
+use retry::{Failure, Retry};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::net::TcpStream;
-use tokio::time::timeout;
+use streaming::RequestError;
+use throttle::RetryThrottle;
+use tokio::time::Instant;
+use tower::util::BoxCloneService;
+use tower::{BoxError, Service, ServiceExt};
 
 pub const DEFAULT_MAX_FRAME_SIZE: usize = 4 * 1024 * 1024; // 4MB
 
-#[derive(Debug)]
+// Response encodings the client can decompress, sent as `grpc-accept-encoding`.
+const ACCEPT_ENCODING: &str = "identity, deflate, gzip";
+// The library's part of the `user-agent` header.
+const USER_AGENT: &str = concat!("grpc-rust-grpc_project/", env!("CARGO_PKG_VERSION"));
+
+/// Smallest and largest HTTP/2 `SETTINGS_MAX_FRAME_SIZE` values.
+pub const MIN_HTTP2_FRAME_SIZE: u32 = 16_384;
+pub const MAX_HTTP2_FRAME_SIZE: u32 = 16_777_215;
+
+/// Largest HTTP/2 flow-control window.
+pub const MAX_WINDOW_SIZE: u32 = (1 << 31) - 1;
+
+type BoxedService = BoxCloneService<Request<Body>, Response<Body>, Error>;
+
+/// gRPC client. Cloning is cheap and every clone shares the same channel, so
+/// a single client can serve many concurrent calls.
 pub struct Client {
-    connection: Connection<Http2Transport>,
-    #[allow(dead_code)]
+    // The mutex only guards cloning the service; each call runs on its own clone.
+    service: Mutex<BoxedService>,
+    // Set when the client owns its channel, so `close` can shut it down.
+    channel: Option<Channel>,
+    // Shared by every clone so all calls draw from one retry budget.
+    throttle: Option<Arc<RetryThrottle>>,
     config: ClientConfig,
 }
 
+/// Settings for a client and its channel.
+///
+/// New settings may be added in any release, so configs are built with
+/// [`ClientConfig::builder`] or by changing the fields of
+/// `ClientConfig::default()`, not with struct literals.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct ClientConfig {
     pub max_message_size: usize,
     pub max_concurrent_streams: u32,
     pub enable_http2_keepalive: bool,
     pub http2_keepalive_interval: Duration,
+    pub http2_keepalive_timeout: Duration,
+    pub http2_keepalive_while_idle: bool,
+    /// HTTP/2 flow-control window for each stream, in bytes.
+    pub http2_initial_stream_window_size: u32,
+    /// HTTP/2 flow-control window shared by all streams of a connection.
+    pub http2_initial_connection_window_size: u32,
+    /// Largest HTTP/2 frame payload the client accepts.
+    pub http2_max_frame_size: u32,
+    /// Largest response header list the client accepts, as sized by HTTP/2.
+    pub http2_max_header_list_size: u32,
+    /// Size of the HPACK table used to decode response headers.
+    pub http2_header_table_size: u32,
+    /// Grows the stream and connection windows past their initial sizes to
+    /// the bandwidth-delay product measured with PING round trips, up to
+    /// 16 MiB.
+    pub http2_adaptive_window: bool,
+    pub tcp_nodelay: bool,
+    /// Idle time before TCP keepalive probes are sent; `None` leaves TCP
+    /// keepalive off.
+    pub tcp_keepalive: Option<Duration>,
     pub connect_timeout: Duration,
+    pub connection_backoff: BackoffConfig,
+    pub idle_timeout: Option<Duration>,
+    pub drain_timeout: Duration,
+    /// How calls are spread across the addresses of a balanced channel.
+    pub load_balancing_policy: LoadBalancingPolicy,
+    /// Opens the streams connections run over. `None` dials TCP and Unix
+    /// sockets, applying the `tcp_*` options above.
+    pub connector: Option<Arc<dyn Connector>>,
+    /// HTTP CONNECT proxy for TCP connections.
+    pub proxy: ProxyConfig,
+    /// `:authority` sent with every call. `None` uses the target's host and
+    /// port, or `localhost` for Unix sockets and in-process channels.
+    pub authority: Option<String>,
+    /// Application name and version put before the library's own in the
+    /// `user-agent` header, such as `my-app/1.2`.
+    pub user_agent: Option<String>,
+    /// Sends call credentials over connections that are not secure. Meant
+    /// for local testing; tokens would otherwise travel in plain text.
+    pub allow_insecure_credentials: bool,
+    /// Deadline applied to every call, covering all retry attempts.
+    pub timeout: Option<Duration>,
+    pub retry_policy: Option<RetryPolicy>,
+    /// Hedging policies keyed by full method path, e.g.
+    /// `/package.Service/Method`. Hedged methods are never retried.
+    pub hedging_policies: HashMap<String, HedgingPolicy>,
+    /// Budget shared by retries and hedges across all calls on the client.
+    pub retry_throttling: Option<RetryThrottling>,
+    /// Per-method settings; a matching entry takes precedence over the
+    /// client-wide settings above.
+    pub service_config: Option<ServiceConfig>,
 }
 
 impl Default for ClientConfig {
@@ -34,118 +141,911 @@ impl Default for ClientConfig {
             max_concurrent_streams: 100,
             enable_http2_keepalive: true,
             http2_keepalive_interval: Duration::from_secs(300),
+            http2_keepalive_timeout: Duration::from_secs(20),
+            http2_keepalive_while_idle: false,
+            http2_initial_stream_window_size: DEFAULT_MAX_MESSAGE_SIZE as u32,
+            http2_initial_connection_window_size: DEFAULT_MAX_MESSAGE_SIZE as u32,
+            http2_max_frame_size: MIN_HTTP2_FRAME_SIZE,
+            http2_max_header_list_size: 16 * 1024 * 1024,
+            http2_header_table_size: 4096,
+            http2_adaptive_window: false,
+            tcp_nodelay: true,
+            tcp_keepalive: None,
             connect_timeout: Duration::from_secs(5),
+            connection_backoff: BackoffConfig::default(),
+            idle_timeout: None,
+            drain_timeout: Duration::from_secs(30),
+            load_balancing_policy: LoadBalancingPolicy::default(),
+            connector: None,
+            proxy: ProxyConfig::default(),
+            authority: None,
+            user_agent: None,
+            allow_insecure_credentials: false,
+            timeout: None,
+            retry_policy: None,
+            hedging_policies: HashMap::new(),
+            retry_throttling: None,
+            service_config: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Checks the HTTP/2 and TCP settings against the ranges the protocols
+    /// allow, and rejects sizes and timeouts that would make every call fail.
+    pub fn validate(&self) -> Result<()> {
+        let check = |valid: bool, message: String| {
+            if valid {
+                Ok(())
+            } else {
+                Err(Error::Config(message))
+            }
+        };
+
+        check(
+            self.http2_initial_stream_window_size <= MAX_WINDOW_SIZE,
+            format!(
+                "http2_initial_stream_window_size must be at most {}",
+                MAX_WINDOW_SIZE
+            ),
+        )?;
+        check(
+            (DEFAULT_WINDOW_SIZE..=MAX_WINDOW_SIZE)
+                .contains(&self.http2_initial_connection_window_size),
+            format!(
+                "http2_initial_connection_window_size must be in {}..={}",
+                DEFAULT_WINDOW_SIZE, MAX_WINDOW_SIZE
+            ),
+        )?;
+        check(
+            (MIN_HTTP2_FRAME_SIZE..=MAX_HTTP2_FRAME_SIZE).contains(&self.http2_max_frame_size),
+            format!(
+                "http2_max_frame_size must be in {}..={}",
+                MIN_HTTP2_FRAME_SIZE, MAX_HTTP2_FRAME_SIZE
+            ),
+        )?;
+        check(
+            self.http2_max_header_list_size > 0,
+            "http2_max_header_list_size must be positive".to_string(),
+        )?;
+        check(
+            self.max_message_size > 0,
+            "max_message_size must be positive".to_string(),
+        )?;
+        check(
+            self.max_concurrent_streams > 0,
+            "max_concurrent_streams must be positive".to_string(),
+        )?;
+        check(
+            !self.enable_http2_keepalive
+                || !(self.http2_keepalive_interval.is_zero()
+                    || self.http2_keepalive_timeout.is_zero()),
+            "http2_keepalive_interval and http2_keepalive_timeout must be positive".to_string(),
+        )?;
+        check(
+            self.tcp_keepalive.is_none_or(|time| !time.is_zero()),
+            "tcp_keepalive must be positive".to_string(),
+        )?;
+        check(
+            !self.connect_timeout.is_zero(),
+            "connect_timeout must be positive".to_string(),
+        )?;
+        check(
+            self.idle_timeout.is_none_or(|time| !time.is_zero()),
+            "idle_timeout must be positive".to_string(),
+        )?;
+        check(
+            self.timeout.is_none_or(|time| !time.is_zero()),
+            "timeout must be positive".to_string(),
+        )?;
+        check(
+            self.authority
+                .as_deref()
+                .is_none_or(|authority| authority.parse::<Authority>().is_ok()),
+            "authority must be a valid URI authority".to_string(),
+        )?;
+        check(
+            HeaderValue::try_from(self.user_agent()).is_ok(),
+            "user_agent must be a valid header value".to_string(),
+        )?;
+        if let Some(policy) = &self.retry_policy {
+            policy.validate()?;
+        }
+        if let Some(throttling) = &self.retry_throttling {
+            throttling.validate()?;
+        }
+        for policy in self.hedging_policies.values() {
+            policy.validate()?;
+        }
+        let backoff = &self.connection_backoff;
+        check(
+            backoff.multiplier >= 1.0
+                && backoff.multiplier.is_finite()
+                && (0.0..=1.0).contains(&backoff.jitter),
+            "connection_backoff multiplier must be finite and at least 1, and jitter in 0..=1"
+                .to_string(),
+        )?;
+        check(
+            backoff.base_delay <= backoff.max_delay,
+            "connection_backoff base_delay must not exceed max_delay".to_string(),
+        )
+    }
+
+    /// The `user-agent` header: the application's `user_agent`, if any,
+    /// followed by `grpc-rust-grpc_project/<version>`.
+    pub(crate) fn user_agent(&self) -> String {
+        match &self.user_agent {
+            Some(prefix) => format!("{} {}", prefix, USER_AGENT),
+            None => USER_AGENT.to_string(),
+        }
+    }
+
+    /// The connector subchannels dial with.
+    pub(crate) fn connector(&self) -> Arc<dyn Connector> {
+        match &self.connector {
+            Some(connector) => connector.clone(),
+            None => Arc::new(DefaultConnector {
+                nodelay: self.tcp_nodelay,
+                keepalive: self.tcp_keepalive,
+            }),
         }
     }
 }
 
+impl Clone for Client {
+    fn clone(&self) -> Self {
+        Self {
+            service: Mutex::new(self.service()),
+            channel: self.channel.clone(),
+            throttle: self.throttle.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Client {
     pub async fn connect<T: AsRef<str>>(addr: T, config: Option<ClientConfig>) -> Result<Self> {
         let config = config.unwrap_or_default();
-        let addr_str = addr.as_ref();
+        let channel = Channel::connect(addr, &config).await?;
+
+        Ok(Self::from_channel(channel, Some(config)))
+    }
+
+    /// Creates a client without dialing; the connection is established by the
+    /// first call. Must be called from within a Tokio runtime.
+    pub fn connect_lazy<T: AsRef<str>>(addr: T, config: Option<ClientConfig>) -> Result<Self> {
+        let config = config.unwrap_or_default();
+        let channel = Channel::connect_lazy(addr, &config)?;
+
+        Ok(Self::from_channel(channel, Some(config)))
+    }
+
+    /// Creates a client that balances calls across `addrs` with
+    /// `ClientConfig::load_balancing_policy`. Connections are opened lazily.
+    /// Must be called from within a Tokio runtime.
+    pub fn balance<I, A>(addrs: I, config: Option<ClientConfig>) -> Result<Self>
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<str>,
+    {
+        let config = config.unwrap_or_default();
+        let channel = Channel::balance(addrs, &config)?;
+
+        Ok(Self::from_channel(channel, Some(config)))
+    }
+
+    /// Creates a client that talks to an in-process server through
+    /// `connector`; see [`crate::transport::in_process::pair`].
+    pub fn in_process(connector: InProcessConnector, config: Option<ClientConfig>) -> Self {
+        let config = config.unwrap_or_default();
+        let channel = Channel::in_process(connector, &config);
+
+        Self::from_channel(channel, Some(config))
+    }
+
+    /// Runs the HTTP/2 handshake over an already open `stream`, such as an
+    /// in-memory duplex or a tunnelled socket. The client cannot reconnect
+    /// once the stream closes.
+    pub async fn from_stream<T: Io>(stream: T, config: Option<ClientConfig>) -> Result<Self> {
+        let config = config.unwrap_or_default();
+        let channel = Channel::from_stream(stream, &config).await?;
+
+        Ok(Self::from_channel(channel, Some(config)))
+    }
+
+    /// Builds a client that owns `channel`, so [`Client::close`] shuts it down.
+    pub fn from_channel(channel: Channel, config: Option<ClientConfig>) -> Self {
+        let mut client = Self::with_service(channel.clone(), config);
+        client.channel = Some(channel);
+        client
+    }
+
+    /// Builds a client on top of any cloneable `tower::Service` that speaks
+    /// `http::Request<Body>`, e.g. a [`Channel`] wrapped in tower layers.
+    /// Services that are not `Clone` can be wrapped in `tower::buffer::Buffer`.
+    pub fn with_service<S>(service: S, config: Option<ClientConfig>) -> Self
+    where
+        S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
+        let service = service.map_err(|e| Error::from(e.into()));
+        let config = config.unwrap_or_default();
+        Self {
+            service: Mutex::new(BoxCloneService::new(service)),
+            channel: None,
+            throttle: config
+                .retry_throttling
+                .as_ref()
+                .or_else(|| config.service_config.as_ref()?.retry_throttling.as_ref())
+                .map(|throttling| Arc::new(RetryThrottle::new(throttling))),
+            config,
+        }
+    }
 
-        let uri = Uri::from_str(addr_str)
-            .map_err(|_| Error::Protocol(format!("Invalid URI: {}", addr_str)))?;
+    /// Gracefully closes the client's channel: new calls are rejected and
+    /// in-flight calls get up to `drain_timeout` to complete. Affects every
+    /// clone of this client. Clients built with [`Client::with_service`] do
+    /// not own a channel; shut the service down directly instead.
+    pub async fn close(&self) {
+        if let Some(channel) = &self.channel {
+            channel.shutdown().await;
+        }
+    }
+
+    fn service(&self) -> BoxedService {
+        self.service
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
 
-        if !addr_str.starts_with("http://") && !addr_str.starts_with("https://") {
-            return Err(Error::Protocol(
-                "URI must start with http:// or https://".to_string(),
-            ));
+    /// Whether calls travel over secure connections: those of the owned
+    /// channel, or else those the configured connector opens.
+    fn is_secure(&self) -> bool {
+        match &self.channel {
+            Some(channel) => channel.is_secure(),
+            None => self.config.connector().is_secure(),
         }
+    }
 
-        let host = uri
-            .host()
-            .ok_or_else(|| Error::Protocol("Missing host".to_string()))?;
-        let port = uri.port_u16().unwrap_or(80);
-        let addr = format!("{}:{}", host, port);
+    /// Performs a unary call. `options` accepts [`CallOptions`] or, for calls
+    /// that only carry metadata, an `Option<Metadata>`.
+    ///
+    /// When a retry policy is configured, failed attempts with a retryable
+    /// status are replayed from the buffered request until the policy, the
+    /// server's pushback or the deadline says otherwise.
+    ///
+    /// Cancelling the options' [`CancellationToken`] ends the call with
+    /// `Cancelled` wherever it is waiting.
+    pub async fn unary<T, U>(
+        &self,
+        method: &str,
+        request: T,
+        options: impl Into<CallOptions>,
+    ) -> Result<Response<U>>
+    where
+        T: prost::Message,
+        U: prost::Message + Default,
+    {
+        let options = options.into();
+        match options.cancellation.clone() {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(cancel::cancelled()),
+                response = self.invoke_unary(method, request, options) => response,
+            },
+            None => self.invoke_unary(method, request, options).await,
+        }
+    }
 
-        let stream = timeout(config.connect_timeout, TcpStream::connect(&addr)).await??;
+    async fn invoke_unary<T, U>(
+        &self,
+        method: &str,
+        request: T,
+        options: CallOptions,
+    ) -> Result<Response<U>>
+    where
+        T: prost::Message,
+        U: prost::Message + Default,
+    {
+        let mut settings = self.call_settings(method, options);
+        let deadline = settings.deadline;
+        let message = encode_message(&request, settings.compression, settings.max_request_size)?;
+        self.add_credentials(method, &mut settings).await?;
 
-        let mut builder = h2::client::Builder::new();
-        builder
-            .initial_connection_window_size(
-                config
-                    .max_message_size
-                    .try_into()
-                    .map_err(|_| Error::Protocol("Size conversion failed".to_string()))?,
-            )
-            .initial_window_size(
-                config
-                    .max_message_size
-                    .try_into()
-                    .map_err(|_| Error::Protocol("Size conversion failed".to_string()))?,
-            )
-            .max_concurrent_streams(config.max_concurrent_streams);
+        if let Some(policy) = settings.hedging_policy {
+            let hedged = self.hedge(policy, method, &settings, message);
+            return with_deadline(deadline, hedged).await;
+        }
+
+        let mut retry = match settings.retry_policy {
+            Some(policy) => Retry::new(policy, self.throttle.as_deref()),
+            None => Retry::new(&NO_RETRY, None),
+        };
+
+        loop {
+            let request = self.build_request(
+                method,
+                &settings,
+                retry.previous_attempts(),
+                Body::from(message.clone()),
+            )?;
 
-        let (send_request, connection) =
-            builder.handshake(stream).await.map_err(Error::Transport)?;
+            let attempt = self.attempt(request, settings.max_response_size);
+            let failure = match with_deadline(deadline, attempt).await {
+                Ok(response) => {
+                    self.record_success();
+                    return Ok(response);
+                }
+                Err(failure) => failure,
+            };
 
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                eprintln!("Connection error: {}", e);
+            let delay = match retry.next_delay(&failure) {
+                Some(delay) => delay,
+                None => return Err(failure.error),
+            };
+            if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+                return Err(failure.error);
             }
-        });
 
-        let transport = Http2Transport::new(send_request);
-        let connection = Connection::new(transport).with_max_frame_size(config.max_message_size);
+            tokio::time::sleep(delay).await;
+        }
+    }
 
-        Ok(Self { connection, config })
+    /// Sends the request again every `hedging_delay` until an attempt
+    /// succeeds or fails fatally. Dropping the remaining attempts resets
+    /// their streams.
+    async fn hedge<U>(
+        &self,
+        policy: &HedgingPolicy,
+        method: &str,
+        settings: &CallSettings<'_>,
+        message: Bytes,
+    ) -> Result<Response<U>>
+    where
+        U: prost::Message + Default,
+    {
+        let max_attempts = policy.max_attempts();
+        let mut in_flight = FuturesUnordered::new();
+        let mut sent = 0;
+        let mut next_hedge = Some(Instant::now());
+        let mut last_error = None;
+
+        loop {
+            let throttled = self.throttle.as_ref().is_some_and(|t| !t.allows_retry());
+            if sent > 0 && throttled {
+                next_hedge = None;
+            }
+            if next_hedge.is_some_and(|at| at <= Instant::now()) {
+                let body = Body::from(message.clone());
+                let request = self.build_request(method, settings, sent, body)?;
+                in_flight.push(self.attempt::<U>(request, settings.max_response_size));
+                sent += 1;
+                next_hedge = (sent < max_attempts).then(|| Instant::now() + policy.hedging_delay);
+            }
+
+            if in_flight.is_empty() && next_hedge.is_none() {
+                return Err(last_error
+                    .unwrap_or_else(|| Error::Protocol("No response received".to_string())));
+            }
+
+            let timer = next_hedge;
+            tokio::select! {
+                Some(result) = in_flight.next() => {
+                    let error = match result {
+                        Ok(response) => {
+                            self.record_success();
+                            return Ok(response);
+                        }
+                        // A committed attempt ends the call, like a fatal status.
+                        Err(Failure { error, committed: true }) => return Err(error),
+                        Err(Failure { error, .. }) if !policy.is_non_fatal(&error) => {
+                            return Err(error)
+                        }
+                        Err(failure) => failure.error,
+                    };
+                    if let Some(throttle) = &self.throttle {
+                        throttle.record_failure();
+                    }
+
+                    if sent < max_attempts {
+                        next_hedge = match retry::pushback(&error) {
+                            Some(Some(delay)) => Some(Instant::now() + delay),
+                            Some(None) => None,
+                            // A non-fatal failure sends the next hedge right away.
+                            None => Some(Instant::now()),
+                        };
+                    }
+                    last_error = Some(error);
+                }
+                () = sleep_until(timer) => {}
+            }
+        }
     }
 
-    pub async fn unary<T, U>(
-        &mut self,
+    /// Performs a server-streaming call: one request message, answered by a
+    /// stream of response messages. `options` work as for
+    /// [`unary`](Self::unary), except that streaming calls are neither
+    /// retried nor hedged.
+    pub async fn server_streaming<T, U>(
+        &self,
         method: &str,
         request: T,
-        metadata: Option<Metadata>,
+        options: impl Into<CallOptions>,
+    ) -> Result<Response<Streaming<U>>>
+    where
+        T: prost::Message,
+        U: prost::Message + Default,
+    {
+        let settings = self.call_settings(method, options.into());
+        let message = encode_message(&request, settings.compression, settings.max_request_size)?;
+        let errors = RequestError::default();
+        self.open_stream(method, settings, Body::from(message), errors)
+            .await
+    }
+
+    /// Performs a client-streaming call: the messages of `requests`,
+    /// answered by a single response once the stream ends. `options` work as
+    /// for [`server_streaming`](Self::server_streaming).
+    pub async fn client_streaming<S, T, U>(
+        &self,
+        method: &str,
+        requests: S,
+        options: impl Into<CallOptions>,
     ) -> Result<Response<U>>
     where
+        S: Stream<Item = T> + Send + 'static,
+        T: prost::Message,
+        U: prost::Message + Default,
+    {
+        let settings = self.call_settings(method, options.into());
+        let deadline = settings.deadline;
+        let max_response_size = settings.max_response_size;
+        let errors = RequestError::default();
+        let body = request_stream(requests, &settings, errors.clone());
+
+        let call = async {
+            let response = self.start_call(method, settings, body).await?;
+            read_response(response, max_response_size).await
+        };
+        with_deadline(deadline, call)
+            .await
+            .map_err(|error| errors.take_or(error))
+    }
+
+    /// Performs a bidirectional streaming call: the messages of `requests`
+    /// are sent while the response messages arrive, so each side can react
+    /// to the other. `options` work as for
+    /// [`server_streaming`](Self::server_streaming).
+    pub async fn streaming<S, T, U>(
+        &self,
+        method: &str,
+        requests: S,
+        options: impl Into<CallOptions>,
+    ) -> Result<Response<Streaming<U>>>
+    where
+        S: Stream<Item = T> + Send + 'static,
         T: prost::Message,
         U: prost::Message + Default,
     {
+        let settings = self.call_settings(method, options.into());
+        let errors = RequestError::default();
+        let body = request_stream(requests, &settings, errors.clone());
+        self.open_stream(method, settings, body, errors).await
+    }
+
+    /// Starts a call whose response messages are streamed.
+    async fn open_stream<U>(
+        &self,
+        method: &str,
+        settings: CallSettings<'_>,
+        body: Body,
+        errors: RequestError,
+    ) -> Result<Response<Streaming<U>>>
+    where
+        U: prost::Message + Default,
+    {
+        let deadline = settings.deadline;
+        let max_response_size = settings.max_response_size;
+        let response = with_deadline(deadline, self.start_call(method, settings, body))
+            .await
+            .map_err(|error| errors.take_or(error))?;
+
+        // A trailers-only response fails the call before any message.
+        if let Some(status) = Status::from_headers(response.headers()) {
+            if status.code() != Code::Ok {
+                return Err(status.into());
+            }
+        }
+        let (parts, body) = response.into_parts();
+        let messages = Streaming::new(
+            parts.headers.clone(),
+            body,
+            max_response_size,
+            deadline,
+            errors,
+        );
+        Ok(Response::from_parts(parts, messages))
+    }
+
+    /// Sends the request of a streaming call and waits for the response
+    /// head.
+    async fn start_call(
+        &self,
+        method: &str,
+        mut settings: CallSettings<'_>,
+        body: Body,
+    ) -> Result<Response<Body>> {
+        self.add_credentials(method, &mut settings).await?;
+        let request = self.build_request(method, &settings, 0, body)?;
+        self.service().ready().await?.call(request).await
+    }
+
+    /// Adds the metadata of the call's credentials, if it has any, refusing
+    /// to send them over an insecure connection unless configured to.
+    async fn add_credentials(&self, method: &str, settings: &mut CallSettings<'_>) -> Result<()> {
+        let Some(credentials) = settings.credentials.clone() else {
+            return Ok(());
+        };
+        if !(self.config.allow_insecure_credentials || self.is_secure()) {
+            return Err(Status::new(
+                Code::Unauthenticated,
+                "Refusing to send call credentials over an insecure connection",
+            )
+            .into());
+        }
+        let metadata = with_deadline(settings.deadline, credentials.metadata(method))
+            .await
+            .map_err(credentials_error)?;
+        settings
+            .metadata
+            .get_or_insert_with(Metadata::new)
+            .extend(metadata);
+        Ok(())
+    }
+
+    /// Resolves the settings for a call to `method`. Call options override a
+    /// matching service config entry, which overrides the client-wide
+    /// settings; timeouts combine to the earlier deadline.
+    fn call_settings(&self, method: &str, options: CallOptions) -> CallSettings<'_> {
+        let config = &self.config;
+        let method_config = config
+            .service_config
+            .as_ref()
+            .and_then(|service_config| service_config.method_config(method));
+
+        let timeout = match (config.timeout, method_config.and_then(|m| m.timeout)) {
+            (Some(client), Some(method)) => Some(client.min(method)),
+            (client, method) => client.or(method),
+        };
+        let deadline = match (timeout, options.deadline) {
+            (Some(timeout), Some(deadline)) => Some(deadline.min(Instant::now() + timeout)),
+            (timeout, deadline) => deadline.or_else(|| timeout.map(|t| Instant::now() + t)),
+        };
+
+        let (retry_policy, hedging_policy) = match method_config {
+            Some(m) if m.retry_policy.is_some() || m.hedging_policy.is_some() => {
+                (m.retry_policy.as_ref(), m.hedging_policy.as_ref())
+            }
+            _ => match config.hedging_policies.get(method) {
+                Some(policy) => (None, Some(policy)),
+                None => (config.retry_policy.as_ref(), None),
+            },
+        };
+
+        let max_size = |limit: Option<usize>| {
+            limit.map_or(config.max_message_size, |limit| {
+                limit.min(config.max_message_size)
+            })
+        };
+
+        CallSettings {
+            deadline,
+            max_request_size: max_size(
+                options
+                    .max_request_message_size
+                    .or_else(|| method_config?.max_request_message_bytes),
+            ),
+            max_response_size: max_size(
+                options
+                    .max_response_message_size
+                    .or_else(|| method_config?.max_response_message_bytes),
+            ),
+            retry_policy,
+            hedging_policy,
+            wait_for_ready: options
+                .wait_for_ready
+                .or_else(|| method_config?.wait_for_ready)
+                .unwrap_or(false),
+            metadata: options.metadata,
+            compression: options.compression.unwrap_or(CompressionEncoding::None),
+            authority: options.authority,
+            credentials: options.credentials,
+            cancellation: options.cancellation,
+        }
+    }
+
+    fn record_success(&self) {
+        if let Some(throttle) = &self.throttle {
+            throttle.record_success();
+        }
+    }
+
+    fn build_request(
+        &self,
+        method: &str,
+        settings: &CallSettings<'_>,
+        previous_attempts: u32,
+        body: Body,
+    ) -> Result<Request<Body>> {
+        let uri = match &settings.authority {
+            Some(authority) => Uri::builder()
+                .scheme(match self.is_secure() {
+                    true => Scheme::HTTPS,
+                    false => Scheme::HTTP,
+                })
+                .authority(authority.as_str())
+                .path_and_query(method)
+                .build()?,
+            None => Uri::try_from(method).map_err(http::Error::from)?,
+        };
         let mut request_builder = Request::builder()
             .method("POST")
-            .uri(method)
+            .uri(uri)
             .header("content-type", "application/grpc")
-            .header("te", "trailers");
+            .header("te", "trailers")
+            .header("grpc-accept-encoding", ACCEPT_ENCODING)
+            .header("user-agent", self.config.user_agent());
+
+        if settings.compression != CompressionEncoding::None {
+            request_builder =
+                request_builder.header("grpc-encoding", settings.compression.as_str());
+        }
+
+        if let Some(deadline) = settings.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            request_builder = request_builder.header("grpc-timeout", encode_timeout(remaining));
+        }
 
-        if let Some(metadata) = metadata {
+        if previous_attempts > 0 {
+            request_builder =
+                request_builder.header("grpc-previous-rpc-attempts", previous_attempts);
+        }
+
+        if let Some(metadata) = &settings.metadata {
             for (key, value) in metadata.iter() {
                 request_builder = request_builder.header(key.as_str(), value);
             }
         }
 
-        let mut buf = Vec::new();
-        request.encode(&mut buf)?;
+        if settings.wait_for_ready {
+            request_builder = request_builder.extension(WaitForReady);
+        }
 
-        let mut framed_data = Vec::with_capacity(buf.len() + 5);
-        framed_data.push(0);
-        framed_data.extend_from_slice(&(buf.len() as u32).to_be_bytes());
-        framed_data.extend_from_slice(&buf);
+        if let Some(token) = &settings.cancellation {
+            request_builder = request_builder.extension(token.clone());
+        }
 
-        self.connection.send(Bytes::from(framed_data)).await?;
+        Ok(request_builder.body(body)?)
+    }
 
-        if let Some(response_data) = self.connection.receive().await? {
-            if response_data.len() < 5 {
-                return Err(Error::Protocol("Invalid response frame".to_string()));
-            }
+    async fn attempt<U>(
+        &self,
+        request: Request<Body>,
+        max_response_size: usize,
+    ) -> std::result::Result<Response<U>, Failure>
+    where
+        U: prost::Message + Default,
+    {
+        let response = self.service().ready().await?.call(request).await?;
+        // Headers without a status commit the call to this attempt.
+        let committed = !response.headers().contains_key("grpc-status");
+        read_response(response, max_response_size)
+            .await
+            .map_err(|error| Failure { error, committed })
+    }
+}
 
-            let _compressed = response_data[0] != 0;
-            let length = u32::from_be_bytes([
-                response_data[1],
-                response_data[2],
-                response_data[3],
-                response_data[4],
-            ]) as usize;
+/// Encodes and frames one request message, compressing it with
+/// `compression` unless that is `None`.
+fn encode_message<T: prost::Message>(
+    message: &T,
+    compression: CompressionEncoding,
+    max_size: usize,
+) -> Result<Bytes> {
+    let mut buf = Vec::new();
+    message.encode(&mut buf)?;
+    if buf.len() > max_size {
+        return Err(Error::MessageTooLarge(buf.len()));
+    }
+    let compressed = compression != CompressionEncoding::None;
+    if compressed {
+        buf = compression.compression().compress(&buf)?;
+    }
 
-            if response_data.len() < 5 + length {
-                return Err(Error::Protocol("Incomplete response frame".to_string()));
-            }
+    let mut framed_data = BytesMut::with_capacity(buf.len() + 5);
+    Frame::new(Bytes::from(buf), compressed).encode(&mut framed_data);
+    Ok(framed_data.freeze())
+}
 
-            let message_data = &response_data[5..5 + length];
-            let response = U::decode(message_data)?;
-            Ok(Response::new(response))
-        } else {
-            Err(Error::Protocol("No response received".to_string()))
+/// A request body sending each message of `requests` as it is produced.
+/// A message that cannot be sent is recorded in `errors` and resets the
+/// stream.
+fn request_stream<S, T>(requests: S, settings: &CallSettings<'_>, errors: RequestError) -> Body
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: prost::Message,
+{
+    let compression = settings.compression;
+    let max_size = settings.max_request_size;
+    Body::wrap_stream(requests.map(move |message| {
+        // Only the reset reaches the server; the caller gets this error.
+        encode_message(&message, compression, max_size).map_err(|error| {
+            errors.set(error);
+            Error::Protocol("Request message could not be sent".to_string())
+        })
+    }))
+}
+
+/// Reads the single message of a unary response, or the status it failed
+/// with.
+async fn read_response<U>(response: Response<Body>, max_response_size: usize) -> Result<Response<U>>
+where
+    U: prost::Message + Default,
+{
+    let mut codec = GrpcCodec::new().with_max_message_size(max_response_size);
+    let (parts, mut body) = response.into_parts();
+
+    // Stop reading once the body can no longer hold one message within
+    // the limit, rather than buffering whatever the server sends.
+    let max_body_size = max_response_size.saturating_add(5);
+    let mut response_data = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        response_data.extend_from_slice(&chunk?);
+        if response_data.len() > max_body_size {
+            return Err(response_too_large("Response", max_response_size));
+        }
+    }
+
+    // Trailers-only responses carry the status in the headers.
+    let status = match body.trailers().await? {
+        Some(trailers) => Status::from_headers(&trailers),
+        None => Status::from_headers(&parts.headers),
+    };
+    if let Some(status) = status {
+        if status.code() != Code::Ok {
+            return Err(status.into());
+        }
+    }
+
+    if response_data.is_empty() {
+        return Err(Error::Protocol("No response received".to_string()));
+    }
+
+    match codec.decode(&mut response_data)? {
+        Some(frame) => {
+            let compressed = frame.header.compressed;
+            let mut data = frame.into_data();
+            if compressed {
+                data = decompress(&parts.headers, &data, max_response_size)?;
+            }
+            let response = U::decode(data)?;
+            Ok(Response::from_parts(parts, response))
+        }
+        None if response_data.len() < 5 => {
+            Err(Error::Protocol("Invalid response frame".to_string()))
         }
+        None => Err(Error::Protocol("Incomplete response frame".to_string())),
+    }
+}
+
+/// Settings for a single call, resolved from its call options, the client
+/// config and any matching service config entry.
+struct CallSettings<'a> {
+    metadata: Option<Metadata>,
+    deadline: Option<Instant>,
+    wait_for_ready: bool,
+    max_request_size: usize,
+    max_response_size: usize,
+    retry_policy: Option<&'a RetryPolicy>,
+    hedging_policy: Option<&'a HedgingPolicy>,
+    compression: CompressionEncoding,
+    authority: Option<String>,
+    credentials: Option<Arc<dyn CallCredentials>>,
+    cancellation: Option<CancellationToken>,
+}
+
+// Used when no retry policy is configured: a single attempt.
+static NO_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: 1,
+    initial_backoff: Duration::ZERO,
+    max_backoff: Duration::ZERO,
+    backoff_multiplier: 1.0,
+    retryable_status_codes: Vec::new(),
+};
+
+async fn with_deadline<T, E: From<Error>>(
+    deadline: Option<Instant>,
+    future: impl Future<Output = std::result::Result<T, E>>,
+) -> std::result::Result<T, E> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .unwrap_or_else(|_| Err(deadline_exceeded().into())),
+        None => future.await,
+    }
+}
+
+async fn sleep_until(at: Option<Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Decompresses a response message flagged as compressed, using the
+/// response's `grpc-encoding`.
+fn decompress(headers: &HeaderMap, data: &[u8], max_size: usize) -> Result<Bytes> {
+    let encoding = headers
+        .get("grpc-encoding")
+        .and_then(|encoding| encoding.to_str().ok())
+        .and_then(CompressionEncoding::from_name)
+        .filter(|encoding| *encoding != CompressionEncoding::None)
+        .ok_or_else(|| {
+            Status::new(
+                Code::Internal,
+                "Compressed response without a supported grpc-encoding",
+            )
+        })?;
+
+    // Inflating stops just past the limit, so a small compressed message
+    // cannot make the client allocate more than `max_size`.
+    let data = encoding.compression().decompress_bounded(data, max_size)?;
+    if data.len() > max_size {
+        return Err(response_too_large("Decompressed response", max_size));
+    }
+    Ok(Bytes::from(data))
+}
+
+fn response_too_large(what: &str, max_size: usize) -> Error {
+    Status::new(
+        Code::ResourceExhausted,
+        format!("{} message is larger than {} bytes", what, max_size),
+    )
+    .into()
+}
+
+// Credential failures end the call as `Unavailable` unless they carry a status.
+fn credentials_error(error: Error) -> Error {
+    match error {
+        Error::Status(status) => Error::Status(status),
+        error => Status::unavailable(format!("Failed to get call credentials: {}", error)).into(),
+    }
+}
+
+fn deadline_exceeded() -> Error {
+    Status::new(Code::DeadlineExceeded, "Deadline exceeded").into()
+}
+
+/// Encodes a `grpc-timeout` value, which allows at most eight digits.
+fn encode_timeout(timeout: Duration) -> String {
+    const MAX: u128 = 99_999_999;
+
+    let millis = timeout.as_millis();
+    if millis <= MAX {
+        format!("{}m", millis)
+    } else if millis / 1000 <= MAX {
+        format!("{}S", millis / 1000)
+    } else {
+        format!("{}H", (millis / 3_600_000).min(MAX))
     }
 }
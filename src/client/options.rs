@@ -0,0 +1,112 @@
+// src/client/options.rs
+use super::credentials::CallCredentials;
+use crate::codec::CompressionEncoding;
+use crate::transport::CancellationToken;
+use crate::Metadata;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Per-call settings passed to [`Client::unary`](super::Client::unary) and
+/// the streaming calls.
+///
+/// Each option set here takes precedence over the matching service config
+/// entry and the client-wide `ClientConfig`. `Option<Metadata>` and
+/// `Metadata` convert into `CallOptions`, so calls that only need metadata
+/// can keep passing it directly.
+#[derive(Clone, Debug, Default)]
+pub struct CallOptions {
+    pub metadata: Option<Metadata>,
+    /// Latest time the call may finish, covering all retry attempts. The
+    /// configured timeouts still apply; the earliest deadline wins.
+    pub deadline: Option<Instant>,
+    /// Queue the call until the channel is `Ready` instead of failing fast
+    /// with `Unavailable` while it is in transient failure. Bounded by the
+    /// call deadline. Overrides the service config's `waitForReady`.
+    pub wait_for_ready: Option<bool>,
+    /// Compresses the request message. Responses in any supported encoding
+    /// are decompressed regardless.
+    pub compression: Option<CompressionEncoding>,
+    /// Largest request message the call may send, before compression.
+    /// Capped at `ClientConfig::max_message_size`.
+    pub max_request_message_size: Option<usize>,
+    /// Largest response message the call accepts, after decompression.
+    /// Capped at `ClientConfig::max_message_size`.
+    pub max_response_message_size: Option<usize>,
+    /// Sent as `:authority` instead of the channel's default.
+    pub authority: Option<String>,
+    pub credentials: Option<Arc<dyn CallCredentials>>,
+    /// Ends the call with `Cancelled` when cancelled, resetting its stream.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl CallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn with_wait_for_ready(mut self, wait_for_ready: bool) -> Self {
+        self.wait_for_ready = Some(wait_for_ready);
+        self
+    }
+
+    pub fn with_compression(mut self, compression: CompressionEncoding) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    pub fn with_max_request_message_size(mut self, size: usize) -> Self {
+        self.max_request_message_size = Some(size);
+        self
+    }
+
+    pub fn with_max_response_message_size(mut self, size: usize) -> Self {
+        self.max_response_message_size = Some(size);
+        self
+    }
+
+    pub fn with_authority(mut self, authority: impl Into<String>) -> Self {
+        self.authority = Some(authority.into());
+        self
+    }
+
+    pub fn with_credentials<C: CallCredentials>(mut self, credentials: C) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+impl From<Option<Metadata>> for CallOptions {
+    fn from(metadata: Option<Metadata>) -> Self {
+        Self {
+            metadata,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<Metadata> for CallOptions {
+    fn from(metadata: Metadata) -> Self {
+        Some(metadata).into()
+    }
+}
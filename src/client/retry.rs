@@ -0,0 +1,250 @@
+// src/client/retry.rs
+use super::throttle::RetryThrottle;
+use crate::channel::backoff::scale;
+use crate::status::Code;
+use crate::{Error, Result};
+use rand::Rng;
+use std::time::Duration;
+
+/// Attempts beyond this are ignored, as required by the gRPC retry design.
+pub const MAX_ATTEMPTS_LIMIT: u32 = 5;
+
+const PUSHBACK_HEADER: &str = "grpc-retry-pushback-ms";
+
+/// Retry policy for unary calls, following the gRPC retry design.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Total attempts including the original call; capped at
+    /// [`MAX_ATTEMPTS_LIMIT`].
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub backoff_multiplier: f64,
+    pub retryable_status_codes: Vec<Code>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            retryable_status_codes: vec![Code::Unavailable],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts.clamp(1, MAX_ATTEMPTS_LIMIT)
+    }
+
+    /// Rejects policies that could never retry or whose backoff cannot be
+    /// computed.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_attempts < 2 {
+            return Err(Error::Config(
+                "retry_policy max_attempts must be at least 2".to_string(),
+            ));
+        }
+        if self.initial_backoff.is_zero() || self.max_backoff.is_zero() {
+            return Err(Error::Config(
+                "retry_policy initial_backoff and max_backoff must be positive".to_string(),
+            ));
+        }
+        if !(self.backoff_multiplier.is_finite() && self.backoff_multiplier > 0.0) {
+            return Err(Error::Config(
+                "retry_policy backoff_multiplier must be finite and positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn is_retryable(&self, code: Code) -> bool {
+        self.retryable_status_codes.contains(&code)
+    }
+}
+
+/// A failed attempt. Once the server has sent response headers, other than
+/// a trailers-only response, the call is committed to that attempt, which is
+/// then neither retried nor hedged.
+#[derive(Debug)]
+pub(crate) struct Failure {
+    pub(crate) error: Error,
+    pub(crate) committed: bool,
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Self {
+            error,
+            committed: false,
+        }
+    }
+}
+
+/// Tracks the attempts of a single call and decides whether, and after how
+/// long, a failed attempt may be retried.
+#[derive(Debug)]
+pub(crate) struct Retry<'a> {
+    policy: &'a RetryPolicy,
+    throttle: Option<&'a RetryThrottle>,
+    attempts: u32,
+    backoff: Duration,
+}
+
+impl<'a> Retry<'a> {
+    pub(crate) fn new(policy: &'a RetryPolicy, throttle: Option<&'a RetryThrottle>) -> Self {
+        Self {
+            policy,
+            throttle,
+            attempts: 1,
+            backoff: policy.initial_backoff,
+        }
+    }
+
+    /// Number of attempts already made before the current one.
+    pub(crate) fn previous_attempts(&self) -> u32 {
+        self.attempts - 1
+    }
+
+    /// Returns the delay before the next attempt, or `None` when the failure
+    /// must be returned to the caller.
+    pub(crate) fn next_delay(&mut self, failure: &Failure) -> Option<Duration> {
+        let error = &failure.error;
+        let code = status_code(error)?;
+        if !self.policy.is_retryable(code) {
+            return None;
+        }
+
+        if let Some(throttle) = self.throttle {
+            throttle.record_failure();
+            if !throttle.allows_retry() {
+                return None;
+            }
+        }
+
+        if failure.committed || self.attempts >= self.policy.max_attempts() {
+            return None;
+        }
+
+        let delay = match pushback(error) {
+            // Server pushback replaces the computed delay and restarts the
+            // backoff sequence.
+            Some(Some(delay)) => {
+                self.backoff = self.policy.initial_backoff;
+                delay
+            }
+            Some(None) => return None,
+            None => {
+                let max_backoff = self.policy.max_backoff;
+                let max = self.backoff.min(max_backoff);
+                // In f64 so the backoff saturates at `max_backoff` instead of
+                // overflowing `Duration`.
+                self.backoff = scale(self.backoff, self.policy.backoff_multiplier)
+                    .unwrap_or(max_backoff)
+                    .min(max_backoff);
+                rand::thread_rng().gen_range(Duration::ZERO..=max)
+            }
+        };
+
+        self.attempts += 1;
+        Some(delay)
+    }
+}
+
+pub(crate) fn status_code(error: &Error) -> Option<Code> {
+    match error {
+        Error::Status(status) => Some(status.code()),
+        // The server refused the stream before processing it.
+        Error::Transport(e) if e.reason() == Some(h2::Reason::REFUSED_STREAM) => {
+            Some(Code::Unavailable)
+        }
+        _ => None,
+    }
+}
+
+/// `None` when no pushback was sent, `Some(None)` when the server asked the
+/// client not to retry.
+pub(crate) fn pushback(error: &Error) -> Option<Option<Duration>> {
+    let Error::Status(status) = error else {
+        return None;
+    };
+    let value = status.metadata().get(PUSHBACK_HEADER)?;
+
+    Some(
+        value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_millis),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Status;
+
+    fn unavailable() -> Failure {
+        Error::from(Status::unavailable("try again")).into()
+    }
+
+    #[test]
+    fn test_retry_stops_at_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            ..RetryPolicy::default()
+        };
+        let mut retry = Retry::new(&policy, None);
+
+        let retries = std::iter::from_fn(|| retry.next_delay(&unavailable())).count();
+        assert_eq!(retries as u32, MAX_ATTEMPTS_LIMIT - 1);
+    }
+
+    #[test]
+    fn test_retry_backoff_saturates_at_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            backoff_multiplier: 1e30,
+            ..RetryPolicy::default()
+        };
+        let mut retry = Retry::new(&policy, None);
+
+        while let Some(delay) = retry.next_delay(&unavailable()) {
+            assert!(delay <= Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_stays_under_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+            backoff_multiplier: 4.0,
+            ..RetryPolicy::default()
+        };
+        let mut retry = Retry::new(&policy, None);
+
+        while let Some(delay) = retry.next_delay(&unavailable()) {
+            assert!(delay <= Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_committed_attempt_is_not_retried() {
+        let policy = RetryPolicy::default();
+        let mut retry = Retry::new(&policy, None);
+
+        let committed = Failure {
+            committed: true,
+            ..unavailable()
+        };
+        assert_eq!(retry.next_delay(&committed), None);
+        assert_eq!(retry.previous_attempts(), 0);
+    }
+}
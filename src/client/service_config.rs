@@ -0,0 +1,369 @@
+// src/client/service_config.rs
+use super::hedging::HedgingPolicy;
+use super::retry::{RetryPolicy, MAX_ATTEMPTS_LIMIT};
+use super::throttle::{RetryThrottling, MAX_TOKENS_LIMIT};
+use crate::status::Code;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The largest duration a protobuf `Duration` can hold, about 10,000 years.
+const MAX_DURATION_SECS: u64 = 315_576_000_000;
+
+/// A generous bound on `backoffMultiplier`; real configs use values near 2.
+const MAX_BACKOFF_MULTIPLIER: f64 = 1000.0;
+
+/// Per-method call settings parsed from a gRPC service config.
+#[derive(Clone, Debug, Default)]
+pub struct MethodConfig {
+    pub timeout: Option<Duration>,
+    pub wait_for_ready: Option<bool>,
+    pub max_request_message_bytes: Option<usize>,
+    pub max_response_message_bytes: Option<usize>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub hedging_policy: Option<HedgingPolicy>,
+}
+
+/// A gRPC service config, as described in the gRPC service config spec.
+///
+/// Each `methodConfig` entry applies to the methods named in it. A name with
+/// both `service` and `method` matches one method, a name with only `service`
+/// matches every method of that service, and an empty name is the default for
+/// all methods. The most specific match wins.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceConfig {
+    // Keyed by `service/method`, `service/` or the empty string.
+    methods: HashMap<String, MethodConfig>,
+    pub retry_throttling: Option<RetryThrottling>,
+}
+
+impl ServiceConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawServiceConfig = serde_json::from_str(json)
+            .map_err(|e| Error::Config(format!("Invalid service config: {}", e)))?;
+
+        let mut methods = HashMap::new();
+        for entry in raw.method_config {
+            let config = entry.parse()?;
+            for name in entry.name {
+                let key = name.key()?;
+                if methods.insert(key.clone(), config.clone()).is_some() {
+                    return Err(Error::Config(format!(
+                        "Duplicate method config name: {}",
+                        key
+                    )));
+                }
+            }
+        }
+
+        let retry_throttling = raw.retry_throttling.map(|t| t.parse()).transpose()?;
+
+        Ok(Self {
+            methods,
+            retry_throttling,
+        })
+    }
+
+    /// Returns the config for a `/package.Service/Method` path.
+    pub fn method_config(&self, path: &str) -> Option<&MethodConfig> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let service = path.split_once('/').map_or(path, |(service, _)| service);
+
+        self.methods
+            .get(path)
+            .or_else(|| self.methods.get(&format!("{}/", service)))
+            .or_else(|| self.methods.get(""))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawServiceConfig {
+    #[serde(default)]
+    method_config: Vec<RawMethodConfig>,
+    retry_throttling: Option<RawRetryThrottling>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMethodConfig {
+    #[serde(default)]
+    name: Vec<RawName>,
+    timeout: Option<String>,
+    wait_for_ready: Option<bool>,
+    max_request_message_bytes: Option<Value>,
+    max_response_message_bytes: Option<Value>,
+    retry_policy: Option<RawRetryPolicy>,
+    hedging_policy: Option<RawHedgingPolicy>,
+}
+
+#[derive(Deserialize)]
+struct RawName {
+    service: Option<String>,
+    method: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRetryPolicy {
+    max_attempts: u32,
+    initial_backoff: String,
+    max_backoff: String,
+    backoff_multiplier: f64,
+    retryable_status_codes: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawHedgingPolicy {
+    max_attempts: u32,
+    hedging_delay: Option<String>,
+    #[serde(default)]
+    non_fatal_status_codes: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRetryThrottling {
+    max_tokens: u32,
+    token_ratio: f64,
+}
+
+impl RawName {
+    fn key(&self) -> Result<String> {
+        let service = self.service.as_deref().unwrap_or_default();
+        match self.method.as_deref().filter(|method| !method.is_empty()) {
+            Some(_) if service.is_empty() => Err(Error::Config(
+                "Method config name has a method but no service".to_string(),
+            )),
+            Some(method) => Ok(format!("{}/{}", service, method)),
+            None if service.is_empty() => Ok(String::new()),
+            None => Ok(format!("{}/", service)),
+        }
+    }
+}
+
+impl RawMethodConfig {
+    fn parse(&self) -> Result<MethodConfig> {
+        if self.retry_policy.is_some() && self.hedging_policy.is_some() {
+            return Err(Error::Config(
+                "Method config cannot have both a retry and a hedging policy".to_string(),
+            ));
+        }
+
+        Ok(MethodConfig {
+            timeout: self.timeout.as_deref().map(parse_duration).transpose()?,
+            wait_for_ready: self.wait_for_ready,
+            max_request_message_bytes: self
+                .max_request_message_bytes
+                .as_ref()
+                .map(parse_size)
+                .transpose()?,
+            max_response_message_bytes: self
+                .max_response_message_bytes
+                .as_ref()
+                .map(parse_size)
+                .transpose()?,
+            retry_policy: self.retry_policy.as_ref().map(|p| p.parse()).transpose()?,
+            hedging_policy: self
+                .hedging_policy
+                .as_ref()
+                .map(|p| p.parse())
+                .transpose()?,
+        })
+    }
+}
+
+impl RawRetryPolicy {
+    fn parse(&self) -> Result<RetryPolicy> {
+        if self.max_attempts < 2 {
+            return Err(Error::Config(
+                "retryPolicy.maxAttempts must be at least 2".to_string(),
+            ));
+        }
+        let initial_backoff = parse_duration(&self.initial_backoff)?;
+        let max_backoff = parse_duration(&self.max_backoff)?;
+        if initial_backoff.is_zero() || max_backoff.is_zero() {
+            return Err(Error::Config(
+                "retryPolicy backoffs must be positive".to_string(),
+            ));
+        }
+        if !(self.backoff_multiplier > 0.0 && self.backoff_multiplier <= MAX_BACKOFF_MULTIPLIER) {
+            return Err(Error::Config(format!(
+                "retryPolicy.backoffMultiplier must be in (0, {}]",
+                MAX_BACKOFF_MULTIPLIER
+            )));
+        }
+        if self.retryable_status_codes.is_empty() {
+            return Err(Error::Config(
+                "retryPolicy.retryableStatusCodes must not be empty".to_string(),
+            ));
+        }
+
+        Ok(RetryPolicy {
+            max_attempts: self.max_attempts.min(MAX_ATTEMPTS_LIMIT),
+            initial_backoff,
+            max_backoff,
+            backoff_multiplier: self.backoff_multiplier,
+            retryable_status_codes: parse_codes(&self.retryable_status_codes)?,
+        })
+    }
+}
+
+impl RawHedgingPolicy {
+    fn parse(&self) -> Result<HedgingPolicy> {
+        if self.max_attempts < 2 {
+            return Err(Error::Config(
+                "hedgingPolicy.maxAttempts must be at least 2".to_string(),
+            ));
+        }
+
+        Ok(HedgingPolicy {
+            max_attempts: self.max_attempts.min(MAX_ATTEMPTS_LIMIT),
+            hedging_delay: self
+                .hedging_delay
+                .as_deref()
+                .map(parse_duration)
+                .transpose()?
+                .unwrap_or_default(),
+            non_fatal_status_codes: parse_codes(&self.non_fatal_status_codes)?,
+        })
+    }
+}
+
+impl RawRetryThrottling {
+    fn parse(&self) -> Result<RetryThrottling> {
+        if self.max_tokens == 0 || self.max_tokens > MAX_TOKENS_LIMIT {
+            return Err(Error::Config(format!(
+                "retryThrottling.maxTokens must be in 1..={}",
+                MAX_TOKENS_LIMIT
+            )));
+        }
+        if !(self.token_ratio.is_finite() && self.token_ratio > 0.0) {
+            return Err(Error::Config(
+                "retryThrottling.tokenRatio must be finite and positive".to_string(),
+            ));
+        }
+
+        Ok(RetryThrottling {
+            max_tokens: self.max_tokens,
+            token_ratio: self.token_ratio,
+        })
+    }
+}
+
+/// Parses a protobuf JSON duration such as `"1.5s"`.
+fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || Error::Config(format!("Invalid duration: {}", value));
+
+    let seconds = value.strip_suffix('s').ok_or_else(invalid)?;
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let whole: u64 = whole.parse().map_err(|_| invalid())?;
+    if whole > MAX_DURATION_SECS {
+        return Err(invalid());
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", fraction).parse().map_err(|_| invalid())?
+    };
+    Ok(Duration::new(whole, nanos))
+}
+
+// Protobuf JSON encodes 64-bit integers as either numbers or strings.
+fn parse_size(value: &Value) -> Result<usize> {
+    let size = match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    };
+    size.and_then(|size| usize::try_from(size).ok())
+        .ok_or_else(|| Error::Config(format!("Invalid message size: {}", value)))
+}
+
+// Status codes may be given by name or by number.
+fn parse_codes(values: &[Value]) -> Result<Vec<Code>> {
+    values
+        .iter()
+        .map(|value| match value {
+            Value::String(name) => name.parse(),
+            Value::Number(number) => number
+                .as_i64()
+                .filter(|code| (0..=16).contains(code))
+                .map(|code| Code::from_i32(code as i32))
+                .ok_or_else(|| Error::Config(format!("Unknown status code: {}", number))),
+            _ => Err(Error::Config(format!("Invalid status code: {}", value))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1s").unwrap(), Duration::from_secs(1));
+        assert_eq!(parse_duration("0.25s").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse_duration("1.000000001s").unwrap(), Duration::new(1, 1));
+        assert!(parse_duration("1").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("1.0000000001s").is_err());
+        assert!(parse_duration("315576000001s").is_err());
+    }
+
+    #[test]
+    fn test_method_config_precedence() {
+        let config = ServiceConfig::from_json(
+            r#"{
+                "methodConfig": [
+                    { "name": [{}], "timeout": "1s" },
+                    { "name": [{ "service": "pkg.Svc" }], "timeout": "2s" },
+                    { "name": [{ "service": "pkg.Svc", "method": "Get" }], "timeout": "3s" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let timeout = |path| config.method_config(path).unwrap().timeout.unwrap();
+        assert_eq!(timeout("/pkg.Svc/Get"), Duration::from_secs(3));
+        assert_eq!(timeout("/pkg.Svc/List"), Duration::from_secs(2));
+        assert_eq!(timeout("/other.Svc/Get"), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_invalid_configs_are_rejected() {
+        let invalid = [
+            r#"{ "methodConfig": [{ "name": [{ "method": "Get" }] }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}] }, { "name": [{}] }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}], "timeout": "soon" }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}], "retryPolicy": {
+                "maxAttempts": 1, "initialBackoff": "1s", "maxBackoff": "1s",
+                "backoffMultiplier": 2, "retryableStatusCodes": ["UNAVAILABLE"] } }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}], "retryPolicy": {
+                "maxAttempts": 3, "initialBackoff": "1s", "maxBackoff": "1s",
+                "backoffMultiplier": 2, "retryableStatusCodes": ["SOMETIMES"] } }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}], "retryPolicy": {
+                "maxAttempts": 3, "initialBackoff": "1s", "maxBackoff": "1s",
+                "backoffMultiplier": 1e30, "retryableStatusCodes": ["UNAVAILABLE"] } }] }"#,
+            r#"{ "methodConfig": [{ "name": [{}], "retryPolicy": {
+                "maxAttempts": 3, "initialBackoff": "1s", "maxBackoff": "999999999999s",
+                "backoffMultiplier": 2, "retryableStatusCodes": ["UNAVAILABLE"] } }] }"#,
+            r#"{ "retryThrottling": { "maxTokens": 2000, "tokenRatio": 0.1 } }"#,
+        ];
+
+        for json in invalid {
+            assert!(
+                matches!(ServiceConfig::from_json(json), Err(Error::Config(_))),
+                "accepted {}",
+                json
+            );
+        }
+    }
+}
@@ -0,0 +1,162 @@
+// src/client/streaming.rs
+use super::{decompress, response_too_large, with_deadline};
+use crate::codec::{Frame, GrpcCodec};
+use crate::status::{Code, Status};
+use crate::transport::Body;
+use crate::{Error, Result};
+use bytes::BytesMut;
+use http::HeaderMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use tokio::time::Instant;
+
+/// The response messages of a server-streaming or bidirectional call, read
+/// one at a time as the server sends them.
+///
+/// The call's deadline covers every read, and dropping the stream ends the
+/// call, along with any request messages still being sent.
+pub struct Streaming<U> {
+    body: Body,
+    // The response headers, for the encoding of compressed messages and the
+    // status of a trailers-only response.
+    headers: HeaderMap,
+    buf: BytesMut,
+    max_message_size: usize,
+    deadline: Option<Instant>,
+    request_error: RequestError,
+    trailers: Option<HeaderMap>,
+    done: bool,
+    _message: PhantomData<fn() -> U>,
+}
+
+impl<U: prost::Message + Default> Streaming<U> {
+    pub(super) fn new(
+        headers: HeaderMap,
+        body: Body,
+        max_message_size: usize,
+        deadline: Option<Instant>,
+        request_error: RequestError,
+    ) -> Self {
+        Self {
+            body,
+            headers,
+            buf: BytesMut::new(),
+            max_message_size,
+            deadline,
+            request_error,
+            trailers: None,
+            done: false,
+            _message: PhantomData,
+        }
+    }
+
+    /// The next message, or `None` once the server has ended the call with
+    /// an OK status. Any other status is returned as an error.
+    pub async fn message(&mut self) -> Result<Option<U>> {
+        if self.done {
+            return Ok(None);
+        }
+        match self.next_message().await {
+            Ok(Some(message)) => Ok(Some(message)),
+            Ok(None) => {
+                self.done = true;
+                Ok(None)
+            }
+            Err(error) => {
+                self.done = true;
+                Err(self.request_error.take_or(error))
+            }
+        }
+    }
+
+    /// The trailers the server ended the call with, once
+    /// [`message`](Self::message) has returned `None`.
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        self.trailers.as_ref()
+    }
+
+    async fn next_message(&mut self) -> Result<Option<U>> {
+        let mut codec = GrpcCodec::new().with_max_message_size(self.max_message_size);
+        loop {
+            // Refuse an oversized message from its header rather than
+            // buffering all of it.
+            if let Some(len) = self.buf.get(1..5) {
+                let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]);
+                if len as usize > self.max_message_size {
+                    return Err(response_too_large("Response", self.max_message_size));
+                }
+            }
+            if let Some(frame) = codec.decode(&mut self.buf)? {
+                return self.decode(frame).map(Some);
+            }
+
+            let data = async { self.body.data().await.transpose() };
+            match with_deadline(self.deadline, data).await? {
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => return self.finish().await.map(|()| None),
+            }
+        }
+    }
+
+    fn decode(&self, frame: Frame) -> Result<U> {
+        let compressed = frame.header.compressed;
+        let mut data = frame.into_data();
+        if compressed {
+            data = decompress(&self.headers, &data, self.max_message_size)?;
+        }
+        Ok(U::decode(data)?)
+    }
+
+    /// Reads the trailers once the messages have ended and returns the
+    /// status they carry.
+    async fn finish(&mut self) -> Result<()> {
+        let trailers = with_deadline(self.deadline, self.body.trailers()).await?;
+        // Trailers-only responses carry the status in the headers.
+        let status = match &trailers {
+            Some(trailers) => Status::from_headers(trailers),
+            None => Status::from_headers(&self.headers),
+        };
+        self.trailers = trailers;
+        if let Some(status) = status {
+            if status.code() != Code::Ok {
+                return Err(status.into());
+            }
+        }
+        if !self.buf.is_empty() {
+            return Err(Error::Protocol("Incomplete response frame".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl<U> std::fmt::Debug for Streaming<U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Streaming")
+            .field("body", &self.body)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Holds the error that stopped a stream of request messages, such as an
+/// oversized message. The stream is reset when that happens, and the call
+/// reports this error rather than the reset.
+#[derive(Clone, Debug, Default)]
+pub(super) struct RequestError(Arc<Mutex<Option<Error>>>);
+
+impl RequestError {
+    pub(super) fn set(&self, error: Error) {
+        *self.lock() = Some(error);
+    }
+
+    /// The request's error if there was one, else `error`.
+    pub(super) fn take_or(&self, error: Error) -> Error {
+        self.lock().take().unwrap_or(error)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Error>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
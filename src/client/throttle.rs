@@ -0,0 +1,127 @@
+// src/client/throttle.rs
+use crate::{Error, Result};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// `max_tokens` may not exceed this, as required by the gRPC retry design.
+pub const MAX_TOKENS_LIMIT: u32 = 1000;
+
+// Tokens are tracked in thousandths, the precision `token_ratio` allows.
+const SCALE: f64 = 1000.0;
+
+/// Retry throttling parameters, following the gRPC retry design.
+#[derive(Clone, Debug)]
+pub struct RetryThrottling {
+    pub max_tokens: u32,
+    pub token_ratio: f64,
+}
+
+impl Default for RetryThrottling {
+    fn default() -> Self {
+        Self {
+            max_tokens: 10,
+            token_ratio: 0.1,
+        }
+    }
+}
+
+impl RetryThrottling {
+    /// Rejects the values the gRPC retry design does not allow.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_tokens == 0 || self.max_tokens > MAX_TOKENS_LIMIT {
+            return Err(Error::Config(format!(
+                "retry_throttling max_tokens must be in 1..={}",
+                MAX_TOKENS_LIMIT
+            )));
+        }
+        if !(self.token_ratio.is_finite() && self.token_ratio > 0.0) {
+            return Err(Error::Config(
+                "retry_throttling token_ratio must be finite and positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Token bucket shared by every call on a client. Failed attempts take a
+/// token, successful ones give back `token_ratio`; retries and hedges are
+/// only sent while more than half of the tokens remain.
+#[derive(Debug)]
+pub(crate) struct RetryThrottle {
+    max_tokens: u32,
+    token_ratio: u32,
+    tokens: AtomicU32,
+}
+
+impl RetryThrottle {
+    pub(crate) fn new(config: &RetryThrottling) -> Self {
+        let max_tokens = config.max_tokens.min(MAX_TOKENS_LIMIT) * SCALE as u32;
+        Self {
+            max_tokens,
+            token_ratio: (config.token_ratio.max(0.0) * SCALE) as u32,
+            tokens: AtomicU32::new(max_tokens),
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        let _ = self
+            .tokens
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tokens| {
+                Some((tokens + self.token_ratio).min(self.max_tokens))
+            });
+    }
+
+    pub(crate) fn record_failure(&self) {
+        let _ = self
+            .tokens
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tokens| {
+                Some(tokens.saturating_sub(SCALE as u32))
+            });
+    }
+
+    pub(crate) fn allows_retry(&self) -> bool {
+        self.tokens.load(Ordering::Acquire) > self.max_tokens / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_disables_retries_below_half() {
+        let throttle = RetryThrottle::new(&RetryThrottling {
+            max_tokens: 4,
+            token_ratio: 0.5,
+        });
+
+        throttle.record_failure();
+        assert!(throttle.allows_retry());
+        throttle.record_failure();
+        assert!(!throttle.allows_retry());
+
+        throttle.record_success();
+        assert!(throttle.allows_retry());
+    }
+
+    #[test]
+    fn test_throttle_is_bounded() {
+        let throttle = RetryThrottle::new(&RetryThrottling {
+            max_tokens: 2,
+            token_ratio: 1.0,
+        });
+
+        for _ in 0..10 {
+            throttle.record_failure();
+        }
+        throttle.record_success();
+        assert!(!throttle.allows_retry());
+
+        for _ in 0..10 {
+            throttle.record_success();
+        }
+        throttle.record_failure();
+        assert!(!throttle.allows_retry());
+        throttle.record_success();
+        assert!(throttle.allows_retry());
+    }
+}
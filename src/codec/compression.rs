@@ -1,18 +1,63 @@
 // src/codec/compressions.rs
 use crate::Error;
-use flate2::{read::GzDecoder, write::GzEncoder, Compression as GzCompression};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression as GzCompression;
 use std::io::{Read, Write};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressionEncoding {
     None,
     Gzip,
     Deflate,
 }
 
-pub trait Compression {
+impl CompressionEncoding {
+    /// Name used in the `grpc-encoding` and `grpc-accept-encoding` headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionEncoding::None => "identity",
+            CompressionEncoding::Gzip => "gzip",
+            CompressionEncoding::Deflate => "deflate",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "identity" => Some(CompressionEncoding::None),
+            "gzip" => Some(CompressionEncoding::Gzip),
+            "deflate" => Some(CompressionEncoding::Deflate),
+            _ => None,
+        }
+    }
+
+    pub fn compression(&self) -> &'static dyn Compression {
+        match self {
+            CompressionEncoding::None => &NoCompression,
+            CompressionEncoding::Gzip => &GzipCompression,
+            CompressionEncoding::Deflate => &DeflateCompression,
+        }
+    }
+}
+
+pub trait Compression: Send + Sync {
     fn compress(&self, data: &[u8]) -> Result<Vec<u8>, Error>;
     fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Like `decompress`, but stops after `limit + 1` bytes of output, so a
+    /// small message cannot expand without bound. Output longer than
+    /// `limit` means the message is over the limit.
+    fn decompress_bounded(&self, data: &[u8], _limit: usize) -> Result<Vec<u8>, Error> {
+        self.decompress(data)
+    }
+}
+
+fn read_bounded(reader: impl Read, limit: usize) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    reader
+        .take((limit as u64).saturating_add(1))
+        .read_to_end(&mut buf)?;
+    Ok(buf)
 }
 
 pub struct GzipCompression;
@@ -30,6 +75,10 @@ impl Compression for GzipCompression {
         decoder.read_to_end(&mut buf)?;
         Ok(buf)
     }
+
+    fn decompress_bounded(&self, data: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+        read_bounded(GzDecoder::new(data), limit)
+    }
 }
 
 pub struct NoCompression;
@@ -43,3 +92,25 @@ impl Compression for NoCompression {
         Ok(data.to_vec())
     }
 }
+
+/// The gRPC `deflate` encoding: zlib-wrapped DEFLATE.
+pub struct DeflateCompression;
+
+impl Compression for DeflateCompression {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), GzCompression::default());
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut decoder = ZlibDecoder::new(data);
+        let mut buf = Vec::new();
+        decoder.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn decompress_bounded(&self, data: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+        read_bounded(ZlibDecoder::new(data), limit)
+    }
+}
@@ -0,0 +1,23 @@
+// src/codegen.rs
+// Items the client stubs generated by `grpc_project_build` refer to, so
+// crates using them need no direct dependency on `http`.
+
+pub use crate::client::{CallOptions, Streaming};
+pub use crate::{Client, Result};
+pub use futures::Stream;
+pub use http::Response;
+
+/// Includes the code `grpc_project_build` generated for a protobuf package
+/// into the current module.
+///
+/// ```ignore
+/// pub mod helloworld {
+///     grpc_project::include_proto!("helloworld");
+/// }
+/// ```
+#[macro_export]
+macro_rules! include_proto {
+    ($package:tt) => {
+        include!(concat!(env!("OUT_DIR"), concat!("/", $package, ".rs")));
+    };
+}
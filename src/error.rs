@@ -1,4 +1,5 @@
 // src/error.rs
+use crate::status::Status;
 use std::io;
 use thiserror::Error;
 
@@ -48,4 +49,21 @@ pub enum Error {
 
     #[error("Elapsed error: {0}")]
     Elapsed(#[from] tokio::time::error::Elapsed),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Status error: {0}")]
+    Status(#[from] Status),
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for Error {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        // Errors from our own transport come back boxed through tower layers;
+        // unwrap them so callers can still match on the original variant.
+        match err.downcast::<Error>() {
+            Ok(err) => *err,
+            Err(err) => Error::Service(err),
+        }
+    }
 }
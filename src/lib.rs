@@ -1,15 +1,22 @@
 // src/lib.rs
+pub mod channel;
 pub mod client;
 pub mod codec;
+pub mod codegen;
 pub mod error;
 pub mod metadata;
+pub mod status;
 pub mod transport;
 
+pub use channel::Channel;
 pub use client::Client;
 pub use codec::{Frame, GrpcCodec};
 pub use error::Error;
 pub use metadata::Metadata;
-pub use transport::{Connection, Transport};
+pub use status::{Code, Status};
+#[allow(deprecated)]
+pub use transport::Transport;
+pub use transport::{Body, CancellationToken, Connection};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
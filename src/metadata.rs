@@ -15,6 +15,13 @@ impl Metadata {
         Self::default()
     }
 
+    pub(crate) fn from_headers(headers: HeaderMap) -> Self {
+        Self {
+            headers,
+            binary: Default::default(),
+        }
+    }
+
     pub fn insert(&mut self, key: &str, value: &str) -> Result<()> {
         let key = normalize_key(key)?;
         let value = HeaderValue::from_str(value)
@@ -37,6 +44,12 @@ impl Metadata {
     pub fn iter(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
         self.headers.iter()
     }
+
+    /// Adds every entry of `other`, replacing entries with the same key.
+    pub fn extend(&mut self, other: Metadata) {
+        self.headers.extend(other.headers);
+        self.binary.extend(other.binary);
+    }
 }
 
 fn normalize_key(key: &str) -> Result<HeaderName> {
@@ -0,0 +1,172 @@
+// src/status.rs
+use crate::{Error, Metadata, Result};
+use http::HeaderMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// gRPC status codes as defined by the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Code {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+impl Code {
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => Code::Ok,
+            1 => Code::Cancelled,
+            2 => Code::Unknown,
+            3 => Code::InvalidArgument,
+            4 => Code::DeadlineExceeded,
+            5 => Code::NotFound,
+            6 => Code::AlreadyExists,
+            7 => Code::PermissionDenied,
+            8 => Code::ResourceExhausted,
+            9 => Code::FailedPrecondition,
+            10 => Code::Aborted,
+            11 => Code::OutOfRange,
+            12 => Code::Unimplemented,
+            13 => Code::Internal,
+            14 => Code::Unavailable,
+            15 => Code::DataLoss,
+            16 => Code::Unauthenticated,
+            _ => Code::Unknown,
+        }
+    }
+}
+
+impl FromStr for Code {
+    type Err = Error;
+
+    /// Parses the canonical upper-case name, e.g. `UNAVAILABLE`.
+    fn from_str(name: &str) -> Result<Self> {
+        let code = match name {
+            "OK" => Code::Ok,
+            "CANCELLED" => Code::Cancelled,
+            "UNKNOWN" => Code::Unknown,
+            "INVALID_ARGUMENT" => Code::InvalidArgument,
+            "DEADLINE_EXCEEDED" => Code::DeadlineExceeded,
+            "NOT_FOUND" => Code::NotFound,
+            "ALREADY_EXISTS" => Code::AlreadyExists,
+            "PERMISSION_DENIED" => Code::PermissionDenied,
+            "RESOURCE_EXHAUSTED" => Code::ResourceExhausted,
+            "FAILED_PRECONDITION" => Code::FailedPrecondition,
+            "ABORTED" => Code::Aborted,
+            "OUT_OF_RANGE" => Code::OutOfRange,
+            "UNIMPLEMENTED" => Code::Unimplemented,
+            "INTERNAL" => Code::Internal,
+            "UNAVAILABLE" => Code::Unavailable,
+            "DATA_LOSS" => Code::DataLoss,
+            "UNAUTHENTICATED" => Code::Unauthenticated,
+            _ => return Err(Error::Config(format!("Unknown status code: {}", name))),
+        };
+        Ok(code)
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A gRPC status: a code plus a human readable message, along with the
+/// trailing metadata the server sent with it.
+#[derive(Debug, Clone)]
+pub struct Status {
+    code: Code,
+    message: String,
+    // Boxed to keep `Error` small.
+    metadata: Box<Metadata>,
+}
+
+impl Status {
+    pub fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            metadata: Box::default(),
+        }
+    }
+
+    /// Reads `grpc-status` and `grpc-message` from response headers or
+    /// trailers. Returns `None` when no status is present.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let code = headers
+            .get("grpc-status")?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        let message = headers
+            .get("grpc-message")
+            .map(|message| percent_decode(message.as_bytes()))
+            .unwrap_or_default();
+
+        Some(Self {
+            code: Code::from_i32(code),
+            message,
+            metadata: Box::new(Metadata::from_headers(headers.clone())),
+        })
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(Code::Unavailable, message)
+    }
+
+    pub fn code(&self) -> Code {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for Status {}
+
+// `grpc-message` is percent-encoded on the wire.
+fn percent_decode(value: &[u8]) -> String {
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        if value[i] == b'%' && i + 2 < value.len() {
+            let hex = std::str::from_utf8(&value[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(value[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,188 @@
+// src/transport/body.rs
+use super::cancel::{self, CancellationToken};
+use crate::{Error, Result};
+use bytes::Bytes;
+use futures_util::stream::{BoxStream, Stream, StreamExt};
+use h2::{Reason, SendStream};
+use http::HeaderMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Request or response body carried over the service stack.
+///
+/// Outgoing bodies are a single buffer or, for streaming calls, a stream of
+/// chunks sent as they are produced; incoming bodies wrap the h2 receive
+/// stream so data and trailers can be read as they arrive.
+pub struct Body {
+    kind: Kind,
+    // Dropped together with the body, e.g. to release a stream slot on the
+    // channel once the response has been consumed.
+    guards: Vec<Box<dyn BodyGuard>>,
+    cancel: Option<Cancel>,
+}
+
+/// Lets a cancelled call reset its stream while the response is read.
+struct Cancel {
+    token: CancellationToken,
+    // Shared with the task sending a streaming request body.
+    send_stream: Arc<Mutex<SendStream<Bytes>>>,
+}
+
+impl Cancel {
+    fn reset(&mut self) -> Error {
+        self.send_stream
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .send_reset(Reason::CANCEL);
+        cancel::cancelled()
+    }
+}
+
+/// Attached to an incoming body and dropped together with it.
+pub(crate) trait BodyGuard: Send + Sync + 'static {
+    /// Called with the length of each data chunk read from the body.
+    fn on_data(&self, _len: usize) {}
+}
+
+enum Kind {
+    Full(Option<Bytes>),
+    H2(h2::RecvStream),
+    Stream(BoxStream<'static, Result<Bytes>>),
+}
+
+impl Body {
+    pub fn empty() -> Self {
+        Self::new(Kind::Full(None))
+    }
+
+    pub(crate) fn from_h2(stream: h2::RecvStream) -> Self {
+        Self::new(Kind::H2(stream))
+    }
+
+    /// An outgoing body whose chunks are sent as `stream` yields them, such
+    /// as the messages of a client-streaming call. An error resets the
+    /// stream.
+    pub fn wrap_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        Self::new(Kind::Stream(stream.boxed()))
+    }
+
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            guards: Vec::new(),
+            cancel: None,
+        }
+    }
+
+    /// Resets the stream and fails reads with `Cancelled` once `token` is
+    /// cancelled.
+    pub(crate) fn with_cancellation(
+        mut self,
+        token: CancellationToken,
+        send_stream: Arc<Mutex<SendStream<Bytes>>>,
+    ) -> Self {
+        self.cancel = Some(Cancel { token, send_stream });
+        self
+    }
+
+    pub(crate) fn with_guard<G: BodyGuard>(mut self, guard: G) -> Self {
+        self.guards.push(Box::new(guard));
+        self
+    }
+
+    /// Exact length of the remaining data, when known up front.
+    pub fn size_hint(&self) -> Option<usize> {
+        match &self.kind {
+            Kind::Full(data) => Some(data.as_ref().map_or(0, Bytes::len)),
+            Kind::H2(_) | Kind::Stream(_) => None,
+        }
+    }
+
+    pub fn is_end_stream(&self) -> bool {
+        match &self.kind {
+            Kind::Full(data) => data.is_none(),
+            Kind::H2(stream) => stream.is_end_stream(),
+            Kind::Stream(_) => false,
+        }
+    }
+
+    /// Whether this is an outgoing body built with [`Body::wrap_stream`].
+    pub(crate) fn is_streaming(&self) -> bool {
+        matches!(self.kind, Kind::Stream(_))
+    }
+
+    pub async fn data(&mut self) -> Option<Result<Bytes>> {
+        match &mut self.kind {
+            Kind::Full(data) => data.take().map(Ok),
+            Kind::Stream(stream) => stream.next().await,
+            Kind::H2(stream) => {
+                let data = match &mut self.cancel {
+                    Some(cancel) => tokio::select! {
+                        biased;
+                        _ = cancel.token.cancelled() => return Some(Err(cancel.reset())),
+                        data = stream.data() => data,
+                    },
+                    None => stream.data().await,
+                };
+                let chunk = match data? {
+                    Ok(chunk) => chunk,
+                    Err(e) => return Some(Err(Error::Transport(e))),
+                };
+                // Hand the window back as soon as the chunk is buffered.
+                let _ = stream.flow_control().release_capacity(chunk.len());
+                for guard in &self.guards {
+                    guard.on_data(chunk.len());
+                }
+                Some(Ok(chunk))
+            }
+        }
+    }
+
+    pub async fn trailers(&mut self) -> Result<Option<HeaderMap>> {
+        match &mut self.kind {
+            Kind::Full(_) | Kind::Stream(_) => Ok(None),
+            Kind::H2(stream) => {
+                let trailers = match &mut self.cancel {
+                    Some(cancel) => tokio::select! {
+                        biased;
+                        _ = cancel.token.cancelled() => return Err(cancel.reset()),
+                        trailers = stream.trailers() => trailers,
+                    },
+                    None => stream.trailers().await,
+                };
+                trailers.map_err(Error::Transport)
+            }
+        }
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<Bytes> for Body {
+    fn from(data: Bytes) -> Self {
+        Self::new(Kind::Full(Some(data)))
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(data: Vec<u8>) -> Self {
+        Bytes::from(data).into()
+    }
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Full(data) => f.debug_tuple("Body::Full").field(data).finish(),
+            Kind::H2(stream) => f.debug_tuple("Body::H2").field(stream).finish(),
+            Kind::Stream(_) => f.write_str("Body::Stream"),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// src/transport/cancel.rs
+use crate::status::{Code, Status};
+use crate::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Cancels calls from outside their futures.
+///
+/// Pass a token with [`CallOptions`](crate::client::CallOptions), or add it
+/// as an extension to a request sent through a `Channel`. Cancelling resets
+/// the call's stream with `RST_STREAM(CANCEL)` and fails the call, and any
+/// response body still being read, with `Cancelled`. Clones share state, so
+/// one token can cancel several calls.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            self.inner.notify.notify_waiters();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+pub(crate) fn cancelled() -> Error {
+    Status::new(Code::Cancelled, "Call cancelled").into()
+}
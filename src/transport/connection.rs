@@ -1,7 +1,13 @@
 // src/transport/connection.rs
+use crate::transport::Body;
+#[allow(deprecated)]
 use crate::transport::Transport;
-use crate::Result;
+use crate::{Error, Result};
 use bytes::Bytes;
+use futures_util::future::{self, BoxFuture, FutureExt};
+use http::{Request, Response};
+use std::task::{Context, Poll};
+use tower::Service;
 
 #[derive(Debug)]
 pub struct Connection<T> {
@@ -9,7 +15,7 @@ pub struct Connection<T> {
     max_frame_size: usize,
 }
 
-impl<T: Transport> Connection<T> {
+impl<T> Connection<T> {
     pub fn new(transport: T) -> Self {
         Self {
             transport,
@@ -21,14 +27,23 @@ impl<T: Transport> Connection<T> {
         self.max_frame_size = size;
         self
     }
+}
 
+#[allow(deprecated)]
+impl<T: Transport> Connection<T> {
+    #[deprecated(
+        note = "responses are not paired with their requests; use the `tower::Service` impl"
+    )]
     pub async fn send(&mut self, data: Bytes) -> Result<()> {
         if data.len() > self.max_frame_size {
-            return Err(crate::Error::MessageTooLarge(data.len()));
+            return Err(Error::MessageTooLarge(data.len()));
         }
         self.transport.send(data).await
     }
 
+    #[deprecated(
+        note = "responses are not paired with their requests; use the `tower::Service` impl"
+    )]
     pub async fn receive(&mut self) -> Result<Option<Bytes>> {
         self.transport.receive().await
     }
@@ -37,3 +52,26 @@ impl<T: Transport> Connection<T> {
         self.transport.close().await
     }
 }
+
+impl<T> Service<Request<Body>> for Connection<T>
+where
+    T: Service<Request<Body>, Response = Response<Body>, Error = Error>,
+    T::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Response<Body>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.transport.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if let Some(len) = request.body().size_hint() {
+            if len > self.max_frame_size {
+                return future::ready(Err(Error::MessageTooLarge(len))).boxed();
+            }
+        }
+        self.transport.call(request).boxed()
+    }
+}
@@ -0,0 +1,93 @@
+// src/transport/connector.rs
+use crate::channel::Address;
+use crate::transport::BoxedIo;
+use crate::{Error, Result};
+use futures_util::future::BoxFuture;
+use socket2::{SockRef, TcpKeepalive};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+/// Opens the byte stream a subchannel runs HTTP/2 over.
+///
+/// A channel calls its connector whenever a subchannel dials, including on
+/// reconnect. Implement it to tunnel through a proxy, set socket options or
+/// connect to an in-memory server. `ClientConfig::connect_timeout` bounds
+/// each call.
+pub trait Connector: Debug + Send + Sync + 'static {
+    fn connect(&self, address: &Address) -> BoxFuture<'static, Result<BoxedIo>>;
+
+    /// Whether the streams this connector opens are encrypted, which makes
+    /// requests carry the `https` scheme.
+    fn is_secure(&self) -> bool {
+        false
+    }
+}
+
+// Lets a caller keep a handle on the connector it configured.
+impl<C: Connector + ?Sized> Connector for Arc<C> {
+    fn connect(&self, address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        (**self).connect(address)
+    }
+
+    fn is_secure(&self) -> bool {
+        (**self).is_secure()
+    }
+}
+
+/// Connects TCP and Unix socket addresses, and looks up `host:port`
+/// addresses with the system resolver.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultConnector {
+    /// Sets `TCP_NODELAY` on TCP connections.
+    pub nodelay: bool,
+    /// Enables TCP keepalive, probing after this much idle time.
+    pub keepalive: Option<Duration>,
+}
+
+impl DefaultConnector {
+    /// Opens a TCP connection with these socket options.
+    pub(crate) async fn connect_tcp(&self, addr: impl ToSocketAddrs) -> Result<TcpStream> {
+        let stream = TcpStream::connect(addr).await?;
+        self.configure(&stream)?;
+        Ok(stream)
+    }
+
+    fn configure(&self, stream: &TcpStream) -> Result<()> {
+        stream.set_nodelay(self.nodelay)?;
+        if let Some(time) = self.keepalive {
+            let keepalive = TcpKeepalive::new().with_time(time);
+            SockRef::from(stream).set_tcp_keepalive(&keepalive)?;
+        }
+        Ok(())
+    }
+}
+
+impl Connector for DefaultConnector {
+    fn connect(&self, address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        let address = address.clone();
+        let options = *self;
+        Box::pin(async move {
+            match address {
+                Address::Tcp(addr) => Ok(Box::new(options.connect_tcp(addr).await?) as BoxedIo),
+                Address::Host(endpoint) => {
+                    Ok(Box::new(options.connect_tcp(endpoint).await?) as BoxedIo)
+                }
+                #[cfg(unix)]
+                Address::Unix(path) => {
+                    Ok(Box::new(tokio::net::UnixStream::connect(path).await?) as BoxedIo)
+                }
+                #[cfg(not(unix))]
+                Address::Unix(path) => Err(Error::Protocol(format!(
+                    "Unix sockets are not available: {}",
+                    path.display()
+                ))),
+                Address::Named(name) => Err(Error::Protocol(format!(
+                    "No connector for address: {}",
+                    name
+                ))),
+            }
+        })
+    }
+}
@@ -1,16 +1,26 @@
 // src/transport/http2.rs
+use crate::transport::body::BodyGuard;
+use crate::transport::cancel::{self, CancellationToken};
+use crate::transport::Body;
+#[allow(deprecated)]
 use crate::transport::Transport;
 use crate::{Error, Result};
 use bytes::Bytes;
-use futures_util::future::BoxFuture;
+use futures_util::future::{self, BoxFuture};
 use h2::client::SendRequest;
-use http::Request;
+use h2::{Reason, SendStream};
+use http::{Request, Response};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
 use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+use tower::Service;
 
 #[derive(Debug)]
 pub struct Http2Transport {
-    #[allow(dead_code)]
-    send_request: SendRequest<Bytes>,
+    // Taken on close; once every sender is gone h2 finishes the remaining
+    // streams and closes the connection with GOAWAY.
+    send_request: Option<SendRequest<Bytes>>,
     receiver: mpsc::UnboundedReceiver<Result<Bytes>>,
     sender: mpsc::UnboundedSender<Result<Bytes>>,
 }
@@ -19,13 +29,15 @@ impl Http2Transport {
     pub fn new(send_request: SendRequest<Bytes>) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
         Self {
-            send_request,
+            send_request: Some(send_request),
             receiver,
             sender,
         }
     }
 }
 
+// Kept for existing callers; new code goes through the `Service` impl below.
+#[allow(deprecated)]
 impl Transport for Http2Transport {
     fn send(&mut self, data: Bytes) -> BoxFuture<'_, Result<()>> {
         let sender = self.sender.clone();
@@ -37,6 +49,8 @@ impl Transport for Http2Transport {
 
             let (response, mut send_stream) = self
                 .send_request
+                .as_mut()
+                .ok_or(Error::ConnectionClosed)?
                 .send_request(request, false)
                 .map_err(Error::Transport)?;
 
@@ -71,6 +85,139 @@ impl Transport for Http2Transport {
     }
 
     fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        self.send_request = None;
         Box::pin(async { Ok(()) })
     }
 }
+
+impl Service<Request<Body>> for Http2Transport {
+    type Response = Response<Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Response<Body>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.send_request.as_mut() {
+            Some(send_request) => send_request.poll_ready(cx).map_err(Error::Transport),
+            None => Poll::Ready(Err(Error::ConnectionClosed)),
+        }
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        match self.send_request.as_mut() {
+            Some(send_request) => send(send_request, request),
+            None => Box::pin(async { Err(Error::ConnectionClosed) }),
+        }
+    }
+}
+
+/// Opens a new h2 stream for `request` on a ready `SendRequest`, streams the
+/// body and resolves with the response head once it arrives. A
+/// [`CancellationToken`] extension on the request resets the stream with
+/// `CANCEL` when cancelled, including while the response body is read.
+///
+/// A body built with [`Body::wrap_stream`] is sent from a task of its own,
+/// since its messages may wait on the responses; the task stops once the
+/// response is dropped.
+pub(crate) fn send(
+    send_request: &mut SendRequest<Bytes>,
+    request: Request<Body>,
+) -> BoxFuture<'static, Result<Response<Body>>> {
+    let (parts, mut body) = request.into_parts();
+    let token = parts.extensions.get::<CancellationToken>().cloned();
+    let end_of_stream = body.is_end_stream();
+    let sent = send_request.send_request(Request::from_parts(parts, ()), end_of_stream);
+
+    Box::pin(async move {
+        let (response, send_stream) = sent.map_err(Error::Transport)?;
+        let send_stream = Arc::new(Mutex::new(send_stream));
+
+        let mut sender = None;
+        let exchange = async {
+            if body.is_streaming() {
+                let task = tokio::spawn(send_body(body, send_stream.clone()));
+                sender = Some(AbortOnDrop(task.abort_handle()));
+            } else if !end_of_stream {
+                while let Some(chunk) = body.data().await {
+                    let chunk = chunk?;
+                    let end = body.is_end_stream();
+                    lock(&send_stream)
+                        .send_data(chunk, end)
+                        .map_err(Error::Transport)?;
+                }
+            }
+            response.await.map_err(Error::Transport)
+        };
+        let response = match &token {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => None,
+                response = exchange => Some(response),
+            },
+            None => Some(exchange.await),
+        };
+
+        let (parts, recv_stream) = match response {
+            Some(response) => response?.into_parts(),
+            None => {
+                lock(&send_stream).send_reset(Reason::CANCEL);
+                return Err(cancel::cancelled());
+            }
+        };
+        let mut body = Body::from_h2(recv_stream);
+        if let Some(sender) = sender {
+            body = body.with_guard(sender);
+        }
+        let body = match token {
+            Some(token) => body.with_cancellation(token, send_stream),
+            None => body,
+        };
+        Ok(Response::from_parts(parts, body))
+    })
+}
+
+/// Sends a streaming request body, waiting for flow-control capacity before
+/// each chunk, and ends the stream after the last one.
+async fn send_body(mut body: Body, send_stream: Arc<Mutex<SendStream<Bytes>>>) {
+    while let Some(chunk) = body.data().await {
+        let mut chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(_) => {
+                lock(&send_stream).send_reset(Reason::CANCEL);
+                return;
+            }
+        };
+        while !chunk.is_empty() {
+            lock(&send_stream).reserve_capacity(chunk.len());
+            let capacity = future::poll_fn(|cx| lock(&send_stream).poll_capacity(cx)).await;
+            let capacity = match capacity {
+                Some(Ok(capacity)) => capacity.min(chunk.len()),
+                // The stream was reset or closed.
+                _ => return,
+            };
+            if lock(&send_stream)
+                .send_data(chunk.split_to(capacity), false)
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+    let _ = lock(&send_stream).send_data(Bytes::new(), true);
+}
+
+/// Stops the task sending a streaming request body when dropped.
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl BodyGuard for AbortOnDrop {}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,59 @@
+// src/transport/in_process.rs
+use crate::channel::Address;
+use crate::transport::{BoxedIo, Connector};
+use crate::{Error, Result};
+use futures_util::future::{self, BoxFuture};
+use tokio::io::DuplexStream;
+use tokio::sync::mpsc;
+
+/// Bytes either side of an in-process connection may buffer before the
+/// writer waits for the reader.
+pub const BUFFER_SIZE: usize = 64 * 1024;
+
+/// The address in-process channels resolve to.
+pub const ADDRESS: &str = "in-process";
+
+/// Creates a connected pair: every dial through the connector yields the
+/// client half of a fresh `tokio::io::duplex`, and the listener yields the
+/// matching server half to run an HTTP/2 server on.
+///
+/// No sockets or timers are involved, so tests run on tokio's paused clock.
+pub fn pair() -> (InProcessConnector, InProcessListener) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (
+        InProcessConnector { sender },
+        InProcessListener { receiver },
+    )
+}
+
+/// Dials the [`InProcessListener`] it was created with.
+#[derive(Clone, Debug)]
+pub struct InProcessConnector {
+    sender: mpsc::UnboundedSender<DuplexStream>,
+}
+
+/// Accepts the server side of in-process connections.
+#[derive(Debug)]
+pub struct InProcessListener {
+    receiver: mpsc::UnboundedReceiver<DuplexStream>,
+}
+
+impl InProcessListener {
+    /// Waits for the next connection. Returns `None` once every connector is
+    /// dropped.
+    pub async fn accept(&mut self) -> Option<DuplexStream> {
+        self.receiver.recv().await
+    }
+}
+
+impl Connector for InProcessConnector {
+    fn connect(&self, _address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        let (client, server) = tokio::io::duplex(BUFFER_SIZE);
+        let result = match self.sender.send(server) {
+            Ok(()) => Ok(Box::new(client) as BoxedIo),
+            // The listener is gone, just like a closed port.
+            Err(_) => Err(Error::ConnectionClosed),
+        };
+        Box::pin(future::ready(result))
+    }
+}
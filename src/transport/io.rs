@@ -0,0 +1,10 @@
+// src/transport/io.rs
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// A byte stream HTTP/2 can run over, such as a TCP or Unix socket.
+pub trait Io: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin + 'static> Io for T {}
+
+/// A type-erased [`Io`], so connections over different sockets share a type.
+pub type BoxedIo = Box<dyn Io>;
@@ -4,12 +4,31 @@ use bytes::Bytes;
 use futures_util::future::BoxFuture;
 use std::fmt::Debug;
 
+pub mod body;
+pub mod cancel;
 pub mod connection;
+pub mod connector;
 pub mod http2;
+pub mod in_process;
+pub mod io;
+pub mod proxy;
 
+pub use body::Body;
+pub use cancel::CancellationToken;
 pub use connection::Connection;
+pub use connector::{Connector, DefaultConnector};
 pub use http2::Http2Transport;
+pub use in_process::{InProcessConnector, InProcessListener};
+pub use io::{BoxedIo, Io};
+pub use proxy::{Proxy, ProxyConfig, ProxyConnector};
 
+/// The original byte-pipe transport API.
+///
+/// `receive` returns whichever response finished first, not the one for the
+/// caller's own `send`, so concurrent callers can read each other's replies.
+/// Send `http::Request<Body>` through the `tower::Service` impls instead,
+/// which keep every request paired with its response.
+#[deprecated(note = "responses are not paired with their requests; use the `tower::Service` impl")]
 pub trait Transport: Debug {
     fn send(&mut self, data: Bytes) -> BoxFuture<'_, Result<()>>;
     fn receive(&mut self) -> BoxFuture<'_, Result<Option<Bytes>>>;
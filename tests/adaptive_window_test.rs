@@ -0,0 +1,169 @@
+// tests/adaptive_window_test.rs
+use std::time::Duration;
+
+use bytes::Bytes;
+use grpc_project::{
+    client::{Client, ClientConfig, ClientConfigBuilder},
+    DEFAULT_WINDOW_SIZE,
+};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+mod common;
+use common::{read_message, send_message, serve_connection, TestRequest, TestResponse};
+
+const LATENCY: Duration = Duration::from_millis(50);
+const RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Replies with `RESPONSE_SIZE` bytes whatever the request.
+async fn handle_download(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let _: TestRequest = read_message(request.into_body()).await;
+    let response = TestResponse {
+        message: "x".repeat(RESPONSE_SIZE),
+    };
+    send_message(respond, &response);
+}
+
+/// Copies bytes from `from` to `to`, delivering each chunk `LATENCY` after
+/// it was read. Bandwidth is unbounded, so throughput is limited only by how
+/// much data the flow-control windows keep in flight.
+fn delay(mut from: ReadHalf<DuplexStream>, mut to: WriteHalf<DuplexStream>) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut buf = vec![0; 64 * 1024];
+        while let Ok(n @ 1..) = from.read(&mut buf).await {
+            let chunk = Bytes::copy_from_slice(&buf[..n]);
+            if tx.send((Instant::now() + LATENCY, chunk)).is_err() {
+                break;
+            }
+        }
+    });
+    tokio::spawn(async move {
+        while let Some((deliver_at, chunk)) = rx.recv().await {
+            tokio::time::sleep_until(deliver_at).await;
+            if to.write_all(&chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Connects a client to `handle_download` over an in-memory link with
+/// `LATENCY` in each direction.
+async fn connect_over_slow_link(config: ClientConfig) -> Client {
+    let (client_io, client_link) = tokio::io::duplex(1024 * 1024);
+    let (server_link, server_io) = tokio::io::duplex(1024 * 1024);
+    let (client_read, client_write) = tokio::io::split(client_link);
+    let (server_read, server_write) = tokio::io::split(server_link);
+    delay(client_read, server_write);
+    delay(server_read, client_write);
+
+    tokio::spawn(serve_connection(server_io, handle_download));
+    Client::from_stream(client_io, Some(config)).await.unwrap()
+}
+
+async fn download(client: &Client) -> usize {
+    let request = TestRequest {
+        message: "download".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Download", request, None)
+        .await
+        .unwrap()
+        .into_body()
+        .message
+        .len()
+}
+
+fn small_windows(adaptive: bool) -> ClientConfig {
+    ClientConfig::builder()
+        .http2_initial_stream_window_size(DEFAULT_WINDOW_SIZE)
+        .http2_initial_connection_window_size(DEFAULT_WINDOW_SIZE)
+        .http2_adaptive_window(adaptive)
+        .build()
+        .unwrap()
+}
+
+/// Time to download `RESPONSE_SIZE` bytes over the slow link.
+async fn measure(config: ClientConfig) -> Duration {
+    let client = connect_over_slow_link(config).await;
+    let start = Instant::now();
+    assert_eq!(download(&client).await, RESPONSE_SIZE);
+    start.elapsed()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_adaptive_window_speeds_up_high_latency_link() {
+    let fixed = measure(small_windows(false)).await;
+    let adaptive = measure(small_windows(true)).await;
+
+    // A 64 KiB window over a 100ms round trip moves 2 MiB in about 32 round
+    // trips; doubling the window each round trip needs only a handful.
+    assert!(fixed >= 2 * LATENCY * 25, "fixed windows took {:?}", fixed);
+    assert!(
+        adaptive * 3 < fixed,
+        "adaptive windows took {:?}, fixed {:?}",
+        adaptive,
+        fixed
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_adaptive_window_keeps_growing_across_calls() {
+    let client = connect_over_slow_link(small_windows(true)).await;
+
+    let start = Instant::now();
+    assert_eq!(download(&client).await, RESPONSE_SIZE);
+    let first = start.elapsed();
+
+    // The second call starts with the windows the first one grew.
+    let start = Instant::now();
+    assert_eq!(download(&client).await, RESPONSE_SIZE);
+    let second = start.elapsed();
+    assert!(second < first, "first {:?}, second {:?}", first, second);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_adaptive_window_shares_pings_with_keepalive() {
+    let config = ClientConfigBuilder::from_config(small_windows(true))
+        .http2_keepalive_interval(Duration::from_millis(20))
+        .http2_keepalive_timeout(Duration::from_secs(1))
+        .http2_keepalive_while_idle(true)
+        .build()
+        .unwrap();
+    let client = connect_over_slow_link(config).await;
+
+    for _ in 0..3 {
+        assert_eq!(download(&client).await, RESPONSE_SIZE);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_adaptive_window_is_off_by_default() {
+    assert!(!ClientConfig::default().http2_adaptive_window);
+
+    // The same small windows, leaving `http2_adaptive_window` at its default.
+    let default = measure(
+        ClientConfig::builder()
+            .http2_initial_stream_window_size(DEFAULT_WINDOW_SIZE)
+            .http2_initial_connection_window_size(DEFAULT_WINDOW_SIZE)
+            .build()
+            .unwrap(),
+    )
+    .await;
+    let adaptive = measure(small_windows(true)).await;
+
+    // The windows never grow, so every round trip moves at most 64 KiB.
+    assert!(default >= 2 * LATENCY * 25, "default took {:?}", default);
+    assert!(
+        adaptive * 3 < default,
+        "adaptive windows took {:?}, default {:?}",
+        adaptive,
+        default
+    );
+}
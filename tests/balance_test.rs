@@ -0,0 +1,177 @@
+// tests/balance_test.rs
+use std::time::Duration;
+
+use grpc_project::{
+    channel::{BackoffConfig, ConnectivityState, LoadBalancer, LoadBalancingPolicy},
+    client::{Client, ClientConfig},
+    Channel, Error,
+};
+
+mod common;
+use common::{send_message, unused_addr, TestRequest, TestResponse, TestServer};
+
+fn balance_config(policy: LoadBalancingPolicy) -> ClientConfig {
+    ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(200))
+        .connection_backoff(BackoffConfig {
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .load_balancing_policy(policy)
+        .build()
+        .unwrap()
+}
+
+/// A server that answers every call with its own name.
+async fn named_server(name: &'static str) -> TestServer {
+    TestServer::start(move |_request, respond| async move {
+        let response = TestResponse {
+            message: name.to_string(),
+        };
+        send_message(respond, &response);
+    })
+    .await
+}
+
+async fn call(client: &Client) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "who".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Who", request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+async fn tally(client: &Client, calls: usize) -> Vec<(String, usize)> {
+    let mut counts = std::collections::BTreeMap::new();
+    for _ in 0..calls {
+        *counts.entry(call(client).await.unwrap()).or_insert(0) += 1;
+    }
+    counts.into_iter().collect()
+}
+
+#[tokio::test]
+async fn test_round_robin_spreads_calls() {
+    let servers = [
+        named_server("a").await,
+        named_server("b").await,
+        named_server("c").await,
+    ];
+    let config = balance_config(LoadBalancingPolicy::RoundRobin);
+    let client = Client::balance(servers.iter().map(|s| &s.addr), Some(config)).unwrap();
+
+    // The first call connects every subchannel.
+    call(&client).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(
+        tally(&client, 30).await,
+        vec![
+            ("a".to_string(), 10),
+            ("b".to_string(), 10),
+            ("c".to_string(), 10)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_round_robin_skips_failed_subchannel() {
+    let a = named_server("a").await;
+    let b = named_server("b").await;
+    let c = named_server("c").await;
+    let config = balance_config(LoadBalancingPolicy::RoundRobin);
+    let client = Client::balance([&a.addr, &b.addr, &c.addr], Some(config)).unwrap();
+
+    call(&client).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    b.stop().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(
+        tally(&client, 10).await,
+        vec![("a".to_string(), 5), ("c".to_string(), 5)]
+    );
+}
+
+#[tokio::test]
+async fn test_pick_first_sticks_to_first_address() {
+    let a = named_server("a").await;
+    let b = named_server("b").await;
+    let config = balance_config(LoadBalancingPolicy::PickFirst);
+    let client = Client::balance([&a.addr, &b.addr], Some(config)).unwrap();
+
+    assert_eq!(tally(&client, 5).await, vec![("a".to_string(), 5)]);
+
+    // Once the first address fails, calls move to the next one.
+    a.stop().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(tally(&client, 5).await, vec![("b".to_string(), 5)]);
+}
+
+#[tokio::test]
+async fn test_pick_first_skips_unreachable_address() {
+    let dead = unused_addr().await;
+    let b = named_server("b").await;
+    let config = balance_config(LoadBalancingPolicy::PickFirst);
+    let channel = Channel::balance([format!("http://{}", dead), b.addr.clone()], &config).unwrap();
+    assert_eq!(channel.state(), ConnectivityState::Idle);
+
+    let client = Client::from_channel(channel.clone(), Some(config));
+    assert_eq!(call(&client).await.unwrap(), "b");
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_all_addresses_down_is_unavailable() {
+    let config = balance_config(LoadBalancingPolicy::RoundRobin);
+    let addrs = [
+        format!("http://{}", unused_addr().await),
+        format!("http://{}", unused_addr().await),
+    ];
+    let client = Client::balance(&addrs, Some(config)).unwrap();
+
+    match call(&client).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), grpc_project::Code::Unavailable),
+        other => panic!("expected Unavailable, got {:?}", other),
+    }
+}
+
+/// Always sends calls to the last ready subchannel and connects them all.
+#[derive(Debug)]
+struct PickLast;
+
+impl LoadBalancer for PickLast {
+    fn pick(&self, states: &[ConnectivityState]) -> Option<usize> {
+        states
+            .iter()
+            .rposition(|state| *state == ConnectivityState::Ready)
+    }
+
+    fn connect(&self, states: &[ConnectivityState]) -> Vec<usize> {
+        (0..states.len())
+            .filter(|index| states[*index] == ConnectivityState::Idle)
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_custom_load_balancer() {
+    let a = named_server("a").await;
+    let b = named_server("b").await;
+    let config = balance_config(LoadBalancingPolicy::PickFirst);
+    let channel = Channel::balance_with([&a.addr, &b.addr], &config, Box::new(PickLast)).unwrap();
+    let client = Client::from_channel(channel, Some(config));
+
+    call(&client).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(tally(&client, 5).await, vec![("b".to_string(), 5)]);
+}
+
+#[tokio::test]
+async fn test_balance_requires_addresses() {
+    let result = Channel::balance(Vec::<String>::new(), &ClientConfig::default());
+    assert!(matches!(result, Err(Error::Config(_))));
+}
@@ -0,0 +1,335 @@
+// tests/call_options_test.rs
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::future::BoxFuture;
+use grpc_project::{
+    client::{CallCredentials, CallOptions, Client, ClientConfig},
+    codec::CompressionEncoding,
+    Code, Error, Metadata,
+};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::{Request, Response};
+use prost::Message;
+
+mod common;
+use common::{send_message, serve_in_process, TestRequest, TestResponse};
+
+async fn call(client: &Client, options: CallOptions) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "options".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, options)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+fn status_code(result: Result<String, Error>) -> Code {
+    match result {
+        Err(Error::Status(status)) => status.code(),
+        other => panic!("expected status error, got {:?}", other),
+    }
+}
+
+/// Reads the single framed request message, undoing its compression.
+async fn read_request(request: Request<RecvStream>) -> TestRequest {
+    let encoding = request
+        .headers()
+        .get("grpc-encoding")
+        .and_then(|encoding| CompressionEncoding::from_name(encoding.to_str().unwrap()));
+    let mut body = request.into_body();
+    let mut data = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.unwrap();
+        let _ = body.flow_control().release_capacity(chunk.len());
+        data.extend_from_slice(&chunk);
+    }
+
+    let message = match data[0] {
+        0 => data[5..].to_vec(),
+        _ => encoding
+            .unwrap()
+            .compression()
+            .decompress(&data[5..])
+            .unwrap(),
+    };
+    TestRequest::decode(message.as_slice()).unwrap()
+}
+
+/// Replies with how the request arrived: its encoding, the message it
+/// carried, its authority and its authorization header.
+async fn handle_describe(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .map_or("-", |value| value.to_str().unwrap())
+            .to_string()
+    };
+    let description = format!(
+        "{} {} {}",
+        header("grpc-encoding"),
+        request.uri().authority().map_or("-", |a| a.as_str()),
+        header("authorization"),
+    );
+    let message = read_request(request).await.message;
+    send_message(
+        respond,
+        &TestResponse {
+            message: format!("{} {}", description, message),
+        },
+    );
+}
+
+/// Echoes the request back gzip-compressed.
+async fn handle_gzip_echo(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let message = read_request(request).await.message;
+    send_gzip(respond, message).await;
+}
+
+/// Replies with `message`, gzip-compressed.
+async fn send_gzip(mut respond: SendResponse<Bytes>, message: String) {
+    let response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .header("grpc-encoding", "gzip")
+        .body(())
+        .unwrap();
+    let mut stream = respond.send_response(response, false).unwrap();
+
+    let encoded = TestResponse { message }.encode_to_vec();
+    let compressed = CompressionEncoding::Gzip
+        .compression()
+        .compress(&encoded)
+        .unwrap();
+    let mut frame = BytesMut::new();
+    frame.put_u8(1);
+    frame.put_u32(compressed.len() as u32);
+    frame.extend_from_slice(&compressed);
+    stream.send_data(frame.freeze(), false).unwrap();
+
+    let mut trailers = http::HeaderMap::new();
+    trailers.insert("grpc-status", "0".parse().unwrap());
+    stream.send_trailers(trailers).unwrap();
+}
+
+async fn handle_slow(_request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    send_message(
+        respond,
+        &TestResponse {
+            message: "late".to_string(),
+        },
+    );
+}
+
+#[derive(Debug)]
+struct Token(&'static str);
+
+impl CallCredentials for Token {
+    fn metadata<'a>(&'a self, _method: &'a str) -> BoxFuture<'a, grpc_project::Result<Metadata>> {
+        Box::pin(async move {
+            let mut metadata = Metadata::new();
+            metadata.insert("authorization", &format!("Bearer {}", self.0))?;
+            Ok(metadata)
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Unreachable;
+
+impl CallCredentials for Unreachable {
+    fn metadata<'a>(&'a self, _method: &'a str) -> BoxFuture<'a, grpc_project::Result<Metadata>> {
+        Box::pin(async { Err(Error::Protocol("token server unreachable".to_string())) })
+    }
+}
+
+#[tokio::test]
+async fn test_defaults_send_plain_request() {
+    let client = serve_in_process(handle_describe, None);
+    assert_eq!(
+        call(&client, CallOptions::new()).await.unwrap(),
+        "- localhost - options"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_deadline_option() {
+    let client = serve_in_process(handle_slow, None);
+
+    let options = CallOptions::new().with_timeout(Duration::from_millis(100));
+    assert_eq!(
+        status_code(call(&client, options).await),
+        Code::DeadlineExceeded
+    );
+
+    // The configured timeout still applies when it is earlier.
+    let config = ClientConfig::builder()
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    let client = serve_in_process(handle_slow, Some(config));
+    let start = tokio::time::Instant::now();
+    let options = CallOptions::new().with_timeout(Duration::from_secs(5));
+    assert_eq!(
+        status_code(call(&client, options).await),
+        Code::DeadlineExceeded
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_request_compression() {
+    let client = serve_in_process(handle_describe, None);
+
+    for encoding in [CompressionEncoding::Gzip, CompressionEncoding::Deflate] {
+        let options = CallOptions::new().with_compression(encoding);
+        assert_eq!(
+            call(&client, options).await.unwrap(),
+            format!("{} localhost - options", encoding.as_str())
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_compressed_response_is_decompressed() {
+    let client = serve_in_process(handle_gzip_echo, None);
+    assert_eq!(call(&client, CallOptions::new()).await.unwrap(), "options");
+
+    // The compressed frame is already over this limit.
+    let options = CallOptions::new().with_max_response_message_size(4);
+    assert_eq!(
+        status_code(call(&client, options).await),
+        Code::ResourceExhausted
+    );
+}
+
+#[tokio::test]
+async fn test_compression_bomb_is_cut_off_at_limit() {
+    // 16 MiB of one repeated byte compresses to about 16 KiB.
+    let message = "x".repeat(16 * 1024 * 1024);
+    let client = serve_in_process(
+        move |_request, respond| send_gzip(respond, message.clone()),
+        None,
+    );
+
+    let options = CallOptions::new().with_max_response_message_size(1024 * 1024);
+    match call(&client, options).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::ResourceExhausted),
+        other => panic!("expected ResourceExhausted, got {:?}", other),
+    }
+}
+
+/// Starts a 16 MiB message and sends 2 MiB of it, then stalls without ever
+/// ending the stream.
+async fn send_endless(mut respond: SendResponse<Bytes>) {
+    let response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .body(())
+        .unwrap();
+    let mut stream = respond.send_response(response, false).unwrap();
+
+    let mut header = BytesMut::new();
+    header.put_u8(0);
+    header.put_u32(16 * 1024 * 1024);
+    stream.send_data(header.freeze(), false).unwrap();
+    for _ in 0..128 {
+        let chunk = Bytes::from(vec![b'x'; 16 * 1024]);
+        stream.send_data(chunk, false).unwrap();
+    }
+    std::future::pending::<()>().await;
+}
+
+#[tokio::test]
+async fn test_oversized_response_is_cut_off_while_reading() {
+    let client = serve_in_process(|_request, respond| send_endless(respond), None);
+
+    let options = CallOptions::new().with_max_response_message_size(1024 * 1024);
+    let result = tokio::time::timeout(Duration::from_secs(5), call(&client, options))
+        .await
+        .expect("the client kept reading past the limit");
+    assert_eq!(status_code(result), Code::ResourceExhausted);
+}
+
+#[tokio::test]
+async fn test_message_at_size_limit_is_allowed() {
+    let request = TestRequest {
+        message: "options".to_string(),
+    };
+    let limit = request.encoded_len();
+    let config = ClientConfig::builder()
+        .max_message_size(limit)
+        .build()
+        .unwrap();
+    let client = serve_in_process(common::handle_echo, Some(config));
+
+    let options = CallOptions::new()
+        .with_max_request_message_size(limit)
+        .with_max_response_message_size(limit);
+    assert_eq!(call(&client, options).await.unwrap(), "options");
+}
+
+#[tokio::test]
+async fn test_max_message_size_options() {
+    let client = serve_in_process(handle_describe, None);
+
+    let options = CallOptions::new().with_max_request_message_size(4);
+    assert!(matches!(
+        call(&client, options).await,
+        Err(Error::MessageTooLarge(_))
+    ));
+
+    let options = CallOptions::new().with_max_response_message_size(8);
+    assert_eq!(
+        status_code(call(&client, options).await),
+        Code::ResourceExhausted
+    );
+
+    // The client-wide limit still caps both directions.
+    let config = ClientConfig::builder().max_message_size(4).build().unwrap();
+    let client = serve_in_process(handle_describe, Some(config));
+    let options = CallOptions::new()
+        .with_max_request_message_size(1024)
+        .with_max_response_message_size(1024);
+    assert!(matches!(
+        call(&client, options).await,
+        Err(Error::MessageTooLarge(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_authority_override() {
+    let client = serve_in_process(handle_describe, None);
+    let options = CallOptions::new().with_authority("api.example.com:8443");
+    assert_eq!(
+        call(&client, options).await.unwrap(),
+        "- api.example.com:8443 - options"
+    );
+}
+
+#[tokio::test]
+async fn test_call_credentials() {
+    // In-process connections are not secure.
+    let config = ClientConfig::builder()
+        .allow_insecure_credentials(true)
+        .build()
+        .unwrap();
+    let client = serve_in_process(handle_describe, Some(config));
+
+    let mut metadata = Metadata::new();
+    metadata.insert("authorization", "Basic stale").unwrap();
+    let options = CallOptions::new()
+        .with_metadata(metadata)
+        .with_credentials(Token("fresh"));
+    assert_eq!(
+        call(&client, options).await.unwrap(),
+        "- localhost Bearer fresh options"
+    );
+
+    let options = CallOptions::new().with_credentials(Unreachable);
+    assert_eq!(status_code(call(&client, options).await), Code::Unavailable);
+}
@@ -0,0 +1,171 @@
+// tests/cancellation_test.rs
+use std::future::poll_fn;
+use std::time::Duration;
+
+use bytes::Bytes;
+use grpc_project::{
+    client::{CallOptions, Client, ClientConfig},
+    transport::in_process,
+    Body, CancellationToken, Channel, Code, Error,
+};
+use h2::server::SendResponse;
+use h2::Reason;
+use http::{Request, Response};
+use tokio::sync::mpsc;
+use tower::{Service, ServiceExt};
+
+mod common;
+use common::{serve_connection, serve_in_process, TestRequest, TestResponse};
+
+async fn call(client: &Client, options: CallOptions) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "cancel me".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, options)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+fn status_code<T: std::fmt::Debug>(result: Result<T, Error>) -> Code {
+    match result {
+        Err(Error::Status(status)) => status.code(),
+        other => panic!("expected status error, got {:?}", other),
+    }
+}
+
+/// Never answers; reports how the client reset the stream.
+async fn hang(mut respond: SendResponse<Bytes>, resets: mpsc::UnboundedSender<Reason>) {
+    if let Ok(reason) = poll_fn(|cx| respond.poll_reset(cx)).await {
+        let _ = resets.send(reason);
+    }
+}
+
+/// Sends the response head and one chunk, then waits for the client to
+/// reset the stream.
+async fn stream_one_chunk(mut respond: SendResponse<Bytes>, resets: mpsc::UnboundedSender<Reason>) {
+    let response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .body(())
+        .unwrap();
+    let mut stream = respond.send_response(response, false).unwrap();
+    stream
+        .send_data(Bytes::from_static(b"first chunk"), false)
+        .unwrap();
+    if let Ok(reason) = poll_fn(|cx| stream.poll_reset(cx)).await {
+        let _ = resets.send(reason);
+    }
+}
+
+/// Serves `hang` in process, returning the client and the resets it sees.
+fn serve_hang() -> (Client, mpsc::UnboundedReceiver<Reason>) {
+    let (resets, reset_reasons) = mpsc::unbounded_channel();
+    let client = serve_in_process(move |_, respond| hang(respond, resets.clone()), None);
+    (client, reset_reasons)
+}
+
+#[tokio::test]
+async fn test_cancel_in_flight_call() {
+    let (client, mut reset_reasons) = serve_hang();
+
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel.cancel();
+    });
+
+    let options = CallOptions::new().with_cancellation(token);
+    assert_eq!(status_code(call(&client, options).await), Code::Cancelled);
+    assert_eq!(reset_reasons.recv().await, Some(Reason::CANCEL));
+}
+
+#[tokio::test]
+async fn test_cancelled_token_fails_call_immediately() {
+    let (client, _reset_reasons) = serve_hang();
+
+    let token = CancellationToken::new();
+    token.cancel();
+    assert!(token.is_cancelled());
+
+    let options = CallOptions::new().with_cancellation(token);
+    assert_eq!(status_code(call(&client, options).await), Code::Cancelled);
+}
+
+#[tokio::test]
+async fn test_one_token_cancels_many_calls() {
+    let (client, mut reset_reasons) = serve_hang();
+
+    let token = CancellationToken::new();
+    let calls: Vec<_> = (0..3)
+        .map(|_| {
+            let client = client.clone();
+            let options = CallOptions::new().with_cancellation(token.clone());
+            tokio::spawn(async move { call(&client, options).await })
+        })
+        .collect();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    token.cancel();
+    for call in calls {
+        assert_eq!(status_code(call.await.unwrap()), Code::Cancelled);
+    }
+    for _ in 0..3 {
+        assert_eq!(reset_reasons.recv().await, Some(Reason::CANCEL));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cancel_while_waiting_for_ready() {
+    let config = ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let client = Client::connect_lazy("http://127.0.0.1:1", Some(config)).unwrap();
+
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        cancel.cancel();
+    });
+
+    let options = CallOptions::new()
+        .with_wait_for_ready(true)
+        .with_cancellation(token);
+    assert_eq!(status_code(call(&client, options).await), Code::Cancelled);
+}
+
+#[tokio::test]
+async fn test_cancel_streaming_response() {
+    let (resets, mut reset_reasons) = mpsc::unbounded_channel();
+    let (connector, mut listener) = in_process::pair();
+    tokio::spawn(async move {
+        let stream = listener.accept().await.unwrap();
+        serve_connection(stream, move |_, respond| {
+            stream_one_chunk(respond, resets.clone())
+        })
+        .await;
+    });
+    let mut channel = Channel::in_process(connector, &ClientConfig::default());
+
+    let token = CancellationToken::new();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/test.service/Stream")
+        .header("content-type", "application/grpc")
+        .extension(token.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = channel.ready().await.unwrap().call(request).await.unwrap();
+    let mut body = response.into_body();
+
+    let first = body.data().await.unwrap().unwrap();
+    assert_eq!(first, Bytes::from_static(b"first chunk"));
+
+    token.cancel();
+    assert_eq!(status_code(body.data().await.unwrap()), Code::Cancelled);
+    assert_eq!(status_code(body.trailers().await), Code::Cancelled);
+    assert_eq!(reset_reasons.recv().await, Some(Reason::CANCEL));
+}
@@ -0,0 +1,116 @@
+// tests/channel_test.rs
+use bytes::Bytes;
+use futures::future::join_all;
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_project::{
+    client::{Client, ClientConfig},
+    Channel,
+};
+
+mod common;
+use common::{read_message, send_message, serve, setup_echo_server, TestRequest, TestResponse};
+
+fn assert_channel_bounds<T: Clone + Send + Sync + 'static>() {}
+
+#[test]
+fn test_channel_is_clone_send_sync() {
+    assert_channel_bounds::<Channel>();
+    assert_channel_bounds::<Client>();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_parallel_calls_get_their_own_responses() {
+    let addr = setup_echo_server().await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let calls = (0..2000).map(|i| {
+        let client = client.clone();
+        tokio::spawn(async move {
+            let request = TestRequest {
+                message: format!("request-{}", i),
+            };
+            let response = client
+                .unary::<TestRequest, TestResponse>("/test.service/Echo", request, None)
+                .await
+                .unwrap();
+            (i, response.into_body().message)
+        })
+    });
+
+    for result in join_all(calls).await {
+        let (i, message) = result.unwrap();
+        assert_eq!(message, format!("request-{}", i));
+    }
+}
+
+#[tokio::test]
+async fn test_shared_reference_calls() {
+    let addr = setup_echo_server().await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let calls = (0..50).map(|i| {
+        let request = TestRequest {
+            message: i.to_string(),
+        };
+        client.unary::<TestRequest, TestResponse>("/test.service/Echo", request, None)
+    });
+
+    for (i, response) in join_all(calls).await.into_iter().enumerate() {
+        assert_eq!(response.unwrap().into_body().message, i.to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_max_concurrent_streams_is_respected() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let handler = {
+        let in_flight = in_flight.clone();
+        let peak = peak.clone();
+        move |request: Request<RecvStream>, respond: SendResponse<Bytes>| {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(current, Ordering::SeqCst);
+
+                let test_request: TestRequest = read_message(request.into_body()).await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                send_message(
+                    respond,
+                    &TestResponse {
+                        message: test_request.message,
+                    },
+                );
+            }
+        }
+    };
+    let addr = serve(handler).await;
+
+    let config = ClientConfig::builder()
+        .max_concurrent_streams(4)
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    let calls = (0..40).map(|i| {
+        let request = TestRequest {
+            message: i.to_string(),
+        };
+        client.unary::<TestRequest, TestResponse>("/test.service/Slow", request, None)
+    });
+
+    for (i, response) in join_all(calls).await.into_iter().enumerate() {
+        assert_eq!(response.unwrap().into_body().message, i.to_string());
+    }
+    assert!(peak.load(Ordering::SeqCst) <= 4);
+}
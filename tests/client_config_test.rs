@@ -0,0 +1,390 @@
+// tests/client_config_test.rs
+use std::path::PathBuf;
+use std::time::Duration;
+
+use grpc_project::{
+    channel::{BackoffConfig, LoadBalancingPolicy},
+    client::{Client, ClientConfig, HedgingPolicy, RetryPolicy, RetryThrottling},
+    transport::ProxyConfig,
+    Code, Error,
+};
+
+mod common;
+use common::{setup_echo_server, TestRequest, TestResponse};
+
+const TOML: &str = r#"
+max_concurrent_streams = 50
+http2_keepalive_interval = "30s"
+http2_adaptive_window = true
+tcp_keepalive = "1m"
+load_balancing_policy = "round_robin"
+proxy = "none"
+
+[connection_backoff]
+base_delay = "250ms"
+multiplier = 2.0
+
+[service_config]
+methodConfig = [{ name = [{ service = "test.service" }], timeout = "2s" }]
+"#;
+
+/// Writes `contents` to a file in the temp directory, unique to this test.
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_builder_sets_fields() {
+    let config = ClientConfig::builder()
+        .max_concurrent_streams(10)
+        .http2_max_frame_size(32 * 1024)
+        .idle_timeout(Duration::from_secs(60))
+        .load_balancing_policy(LoadBalancingPolicy::RoundRobin)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.max_concurrent_streams, 10);
+    assert_eq!(config.http2_max_frame_size, 32 * 1024);
+    assert_eq!(config.idle_timeout, Some(Duration::from_secs(60)));
+    assert_eq!(
+        config.load_balancing_policy,
+        LoadBalancingPolicy::RoundRobin
+    );
+}
+
+#[test]
+fn test_build_rejects_invalid_settings() {
+    let builders = [
+        ClientConfig::builder().max_message_size(0),
+        ClientConfig::builder().max_concurrent_streams(0),
+        ClientConfig::builder().http2_keepalive_interval(Duration::ZERO),
+        ClientConfig::builder().http2_keepalive_timeout(Duration::ZERO),
+        ClientConfig::builder().connect_timeout(Duration::ZERO),
+        ClientConfig::builder().idle_timeout(Duration::ZERO),
+        ClientConfig::builder().timeout(Duration::ZERO),
+        ClientConfig::builder().http2_max_frame_size(1024),
+        ClientConfig::builder().connection_backoff(BackoffConfig {
+            multiplier: f64::INFINITY,
+            ..Default::default()
+        }),
+        ClientConfig::builder().connection_backoff(BackoffConfig {
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(1),
+            ..Default::default()
+        }),
+        ClientConfig::builder().retry_policy(RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }),
+        ClientConfig::builder().retry_policy(RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..Default::default()
+        }),
+        ClientConfig::builder().retry_policy(RetryPolicy {
+            backoff_multiplier: -2.0,
+            ..Default::default()
+        }),
+        ClientConfig::builder().retry_policy(RetryPolicy {
+            backoff_multiplier: f64::NAN,
+            ..Default::default()
+        }),
+        ClientConfig::builder().hedging_policy(
+            "/test.service/Echo",
+            HedgingPolicy {
+                max_attempts: 1,
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for builder in builders {
+        assert!(
+            matches!(builder.clone().build(), Err(Error::Config(_))),
+            "accepted {:?}",
+            builder
+        );
+    }
+
+    // Keepalive settings only matter while keepalive is on.
+    ClientConfig::builder()
+        .enable_http2_keepalive(false)
+        .http2_keepalive_interval(Duration::ZERO)
+        .build()
+        .unwrap();
+}
+
+#[test]
+fn test_load_toml_file() {
+    let path = write_config("client.toml", TOML);
+    let config = ClientConfig::builder()
+        .file(&path)
+        .unwrap()
+        .build()
+        .unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(config.max_concurrent_streams, 50);
+    assert_eq!(config.http2_keepalive_interval, Duration::from_secs(30));
+    assert!(config.http2_adaptive_window);
+    assert_eq!(config.tcp_keepalive, Some(Duration::from_secs(60)));
+    assert_eq!(
+        config.load_balancing_policy,
+        LoadBalancingPolicy::RoundRobin
+    );
+    assert_eq!(config.proxy, ProxyConfig::Disabled);
+    assert_eq!(
+        config.connection_backoff.base_delay,
+        Duration::from_millis(250)
+    );
+    assert_eq!(config.connection_backoff.multiplier, 2.0);
+
+    let method = config
+        .service_config
+        .as_ref()
+        .and_then(|service_config| service_config.method_config("/test.service/Echo"))
+        .unwrap();
+    assert_eq!(method.timeout, Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn test_load_json_file() {
+    let json = r#"{
+        "max_message_size": 1048576,
+        "idle_timeout": "5m",
+        "proxy": "http://proxy.internal:3128",
+        "service_config": "{\"methodConfig\": []}"
+    }"#;
+    let path = write_config("client.json", json);
+    let config = ClientConfig::builder()
+        .file(&path)
+        .unwrap()
+        .build()
+        .unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(config.max_message_size, 1024 * 1024);
+    assert_eq!(config.idle_timeout, Some(Duration::from_secs(300)));
+    assert_eq!(
+        config.proxy,
+        ProxyConfig::Uri("http://proxy.internal:3128".to_string())
+    );
+    assert!(config.service_config.is_some());
+}
+
+#[test]
+fn test_load_rejects_bad_files() {
+    let sources = [
+        "max_concurrent_streams = 1\nunknown_key = 1",
+        "max_concurrent_streams = \"many\"",
+        "connect_timeout = 5",
+        "connect_timeout = \"5 parsecs\"",
+        "load_balancing_policy = \"random\"",
+        "tcp_nodelay = [true]",
+        "[service_config]\nmethodConfig = 1",
+        "[retry_policy]\nretryable_status_codes = \"UNAVAILABLE, SOMETIMES\"",
+        "[retry_policy]\nmax_attempts = 1",
+        "[retry_throttling]\nmax_tokens = 0",
+        "not toml",
+    ];
+    for source in sources {
+        assert!(
+            matches!(
+                ClientConfig::builder().toml(source).and_then(|b| b.build()),
+                Err(Error::Config(_))
+            ),
+            "accepted {:?}",
+            source
+        );
+    }
+
+    let path = write_config("client.yaml", "max_concurrent_streams: 1");
+    let result = ClientConfig::builder().file(&path);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(result, Err(Error::Config(_))));
+
+    let result = ClientConfig::builder().file("/nonexistent/client.toml");
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[test]
+fn test_env_overrides_file() {
+    // Each test uses its own prefix so parallel tests do not interfere.
+    std::env::set_var("CLIENT_CONFIG_TEST_MAX_CONCURRENT_STREAMS", "25");
+    std::env::set_var("CLIENT_CONFIG_TEST_TCP_KEEPALIVE", "none");
+    std::env::set_var("CLIENT_CONFIG_TEST_CONNECTION_BACKOFF_MAX_DELAY", "10s");
+
+    let config = ClientConfig::builder()
+        .toml(TOML)
+        .unwrap()
+        .env_prefixed("CLIENT_CONFIG_TEST_")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(config.max_concurrent_streams, 25);
+    assert_eq!(config.tcp_keepalive, None);
+    assert_eq!(config.connection_backoff.max_delay, Duration::from_secs(10));
+    // Settings the environment leaves alone keep the file's values.
+    assert_eq!(config.http2_keepalive_interval, Duration::from_secs(30));
+}
+
+const OPTIONAL_TOML: &str = r#"
+tcp_keepalive = "1m"
+idle_timeout = "5m"
+authority = "api.internal"
+user_agent = "my-app/1.0"
+timeout = "3s"
+
+[retry_policy]
+max_attempts = 4
+initial_backoff = "50ms"
+max_backoff = "2s"
+backoff_multiplier = 3.0
+retryable_status_codes = "UNAVAILABLE, ABORTED"
+
+[retry_throttling]
+max_tokens = 20
+token_ratio = 0.5
+
+[service_config]
+methodConfig = []
+"#;
+
+#[test]
+fn test_load_optional_settings() {
+    let path = write_config("optional.toml", OPTIONAL_TOML);
+    let config = ClientConfig::builder()
+        .file(&path)
+        .unwrap()
+        .build()
+        .unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(config.tcp_keepalive, Some(Duration::from_secs(60)));
+    assert_eq!(config.idle_timeout, Some(Duration::from_secs(300)));
+    assert_eq!(config.authority.as_deref(), Some("api.internal"));
+    assert_eq!(config.user_agent.as_deref(), Some("my-app/1.0"));
+    assert_eq!(config.timeout, Some(Duration::from_secs(3)));
+    let policy = config.retry_policy.unwrap();
+    assert_eq!(policy.max_attempts, 4);
+    assert_eq!(policy.initial_backoff, Duration::from_millis(50));
+    assert_eq!(policy.max_backoff, Duration::from_secs(2));
+    assert_eq!(policy.backoff_multiplier, 3.0);
+    assert_eq!(
+        policy.retryable_status_codes,
+        vec![Code::Unavailable, Code::Aborted]
+    );
+    let throttling = config.retry_throttling.unwrap();
+    assert_eq!(throttling.max_tokens, 20);
+    assert_eq!(throttling.token_ratio, 0.5);
+    assert!(config.service_config.is_some());
+}
+
+#[test]
+fn test_env_sets_and_clears_optional_settings() {
+    std::env::set_var("CLIENT_CONFIG_OPTIONAL_TEST_AUTHORITY", "env.internal");
+    std::env::set_var("CLIENT_CONFIG_OPTIONAL_TEST_RETRY_POLICY_MAX_ATTEMPTS", "5");
+    std::env::set_var(
+        "CLIENT_CONFIG_OPTIONAL_TEST_RETRY_THROTTLING_TOKEN_RATIO",
+        "0.25",
+    );
+    let config = ClientConfig::builder()
+        .env_prefixed("CLIENT_CONFIG_OPTIONAL_TEST_")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(config.authority.as_deref(), Some("env.internal"));
+    // Unset fields of a policy keep their defaults.
+    let policy = config.retry_policy.unwrap();
+    assert_eq!(policy.max_attempts, 5);
+    assert_eq!(
+        policy.initial_backoff,
+        RetryPolicy::default().initial_backoff
+    );
+    let throttling = config.retry_throttling.unwrap();
+    assert_eq!(throttling.token_ratio, 0.25);
+    assert_eq!(throttling.max_tokens, RetryThrottling::default().max_tokens);
+
+    let keys = [
+        "TCP_KEEPALIVE",
+        "IDLE_TIMEOUT",
+        "AUTHORITY",
+        "USER_AGENT",
+        "TIMEOUT",
+        "RETRY_POLICY",
+        "RETRY_THROTTLING",
+        "SERVICE_CONFIG",
+    ];
+    for key in keys {
+        std::env::set_var(format!("CLIENT_CONFIG_NONE_TEST_{}", key), "none");
+    }
+    let config = ClientConfig::builder()
+        .toml(OPTIONAL_TOML)
+        .unwrap()
+        .env_prefixed("CLIENT_CONFIG_NONE_TEST_")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(config.tcp_keepalive, None);
+    assert_eq!(config.idle_timeout, None);
+    assert_eq!(config.authority, None);
+    assert_eq!(config.user_agent, None);
+    assert_eq!(config.timeout, None);
+    assert!(config.retry_policy.is_none());
+    assert!(config.retry_throttling.is_none());
+    assert!(config.service_config.is_none());
+}
+
+#[test]
+fn test_env_rejects_unknown_and_invalid_values() {
+    std::env::set_var("CLIENT_CONFIG_UNKNOWN_TEST_MAX_STREAMS", "25");
+    let result = ClientConfig::builder().env_prefixed("CLIENT_CONFIG_UNKNOWN_TEST_");
+    assert!(matches!(result, Err(Error::Config(_))));
+
+    std::env::set_var("CLIENT_CONFIG_INVALID_TEST_TCP_NODELAY", "sometimes");
+    let result = ClientConfig::builder().env_prefixed("CLIENT_CONFIG_INVALID_TEST_");
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_env_tolerates_non_unicode_variables() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let invalid = OsStr::from_bytes(b"\xff\xfe");
+    std::env::set_var("CLIENT_CONFIG_UNRELATED_NON_UNICODE", invalid);
+    std::env::set_var("CLIENT_CONFIG_UNICODE_TEST_MAX_CONCURRENT_STREAMS", "25");
+    let config = ClientConfig::builder()
+        .env_prefixed("CLIENT_CONFIG_UNICODE_TEST_")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(config.max_concurrent_streams, 25);
+
+    std::env::set_var("CLIENT_CONFIG_NON_UNICODE_TEST_USER_AGENT", invalid);
+    let result = ClientConfig::builder().env_prefixed("CLIENT_CONFIG_NON_UNICODE_TEST_");
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_connect_with_built_config() {
+    let addr = setup_echo_server().await;
+    let config = ClientConfig::builder()
+        .toml("max_concurrent_streams = 10\nconnect_timeout = \"2s\"")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    let request = TestRequest {
+        message: "built".to_string(),
+    };
+    let response = client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, None)
+        .await
+        .unwrap();
+    assert_eq!(response.into_body().message, "built");
+}
@@ -0,0 +1,9 @@
+// tests/codegen/build.rs
+fn main() -> std::io::Result<()> {
+    // Use the bundled protoc unless the environment names one.
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().map_err(std::io::Error::other)?;
+        std::env::set_var("PROTOC", protoc);
+    }
+    grpc_project_build::compile_protos(&["proto/helloworld.proto"], &["proto"])
+}
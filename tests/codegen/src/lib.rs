@@ -0,0 +1,4 @@
+// tests/codegen/src/lib.rs
+pub mod helloworld {
+    grpc_project::include_proto!("helloworld");
+}
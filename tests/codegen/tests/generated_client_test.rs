@@ -0,0 +1,152 @@
+// tests/codegen/tests/generated_client_test.rs
+use bytes::{Bytes, BytesMut};
+use futures::channel::mpsc;
+use futures::stream;
+use grpc_project::{Client, Code, Error};
+use grpc_project_codegen_test::helloworld::{GreeterClient, HelloReply, HelloRequest};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+
+#[path = "../../common/mod.rs"]
+mod common;
+use common::{
+    frame, next_message, read_message, send_message, send_status, send_trailers, serve,
+    serve_in_process, start_response,
+};
+
+async fn greet(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let path = request.uri().path().to_string();
+    let (mut body, mut buf) = (request.into_body(), BytesMut::new());
+    match path.as_str() {
+        "/helloworld.Greeter/SayHello" => {
+            let hello: HelloRequest = read_message(body).await;
+            send_message(respond, &greeting(&hello.name));
+        }
+        "/helloworld.Greeter/SayHelloStream" => {
+            let hello: HelloRequest = next_message(&mut body, &mut buf).await.unwrap();
+            let mut send_stream = start_response(respond);
+            for name in [&hello.name, "again"] {
+                send_stream
+                    .send_data(frame(&greeting(name)), false)
+                    .unwrap();
+            }
+            send_trailers(&mut send_stream, 0);
+        }
+        "/helloworld.Greeter/SayHelloToAll" => {
+            let mut names = Vec::new();
+            while let Some(hello) = next_message::<HelloRequest>(&mut body, &mut buf).await {
+                names.push(hello.name);
+            }
+            let mut send_stream = start_response(respond);
+            let reply = greeting(&names.join(" and "));
+            send_stream.send_data(frame(&reply), false).unwrap();
+            send_trailers(&mut send_stream, 0);
+        }
+        "/helloworld.Greeter/Chat" => {
+            let mut send_stream = start_response(respond);
+            while let Some(hello) = next_message::<HelloRequest>(&mut body, &mut buf).await {
+                send_stream
+                    .send_data(frame(&greeting(&hello.name)), false)
+                    .unwrap();
+            }
+            send_trailers(&mut send_stream, 0);
+        }
+        _ => send_status(respond, Code::Unimplemented as i32, &[]),
+    }
+}
+
+fn greeting(name: &str) -> HelloReply {
+    HelloReply {
+        message: format!("Hello, {}!", name),
+    }
+}
+
+fn hello(name: &str) -> HelloRequest {
+    HelloRequest {
+        name: name.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_generated_client_calls_unary_method() {
+    let addr = serve(greet).await;
+    let client = GreeterClient::new(Client::connect(&addr, None).await.unwrap());
+
+    let response = client.say_hello(hello("world"), None).await.unwrap();
+    assert_eq!(response.into_body().message, "Hello, world!");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_generated_client_wraps_existing_client() {
+    let client: GreeterClient = serve_in_process(greet, None).into();
+
+    let response = client.say_hello(hello("in process"), None).await.unwrap();
+    assert_eq!(response.into_body().message, "Hello, in process!");
+
+    // The stub shares the wrapped client and its channel.
+    let inner = client.inner().clone();
+    let reply = inner
+        .unary::<HelloRequest, HelloReply>("/helloworld.Greeter/SayHello", hello("again"), None)
+        .await
+        .unwrap();
+    assert_eq!(reply.into_body().message, "Hello, again!");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_generated_client_surfaces_status() {
+    let client = GreeterClient::new(serve_in_process(
+        |_request, respond| async move { send_status(respond, Code::NotFound as i32, &[]) },
+        None,
+    ));
+
+    match client.say_hello(hello("nobody"), None).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::NotFound),
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_generated_client_calls_server_streaming_method() {
+    let client = GreeterClient::new(serve_in_process(greet, None));
+
+    let response = client.say_hello_stream(hello("world"), None).await.unwrap();
+    let mut replies = response.into_body();
+    let mut messages = Vec::new();
+    while let Some(reply) = replies.message().await.unwrap() {
+        messages.push(reply.message);
+    }
+    assert_eq!(messages, ["Hello, world!", "Hello, again!"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_generated_client_calls_client_streaming_method() {
+    let client = GreeterClient::new(serve_in_process(greet, None));
+
+    let requests = stream::iter([hello("Ann"), hello("Bob")]);
+    let response = client.say_hello_to_all(requests, None).await.unwrap();
+    assert_eq!(response.into_body().message, "Hello, Ann and Bob!");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_generated_client_calls_bidi_streaming_method() {
+    let client = GreeterClient::new(serve_in_process(greet, None));
+    let (sender, requests) = mpsc::unbounded();
+
+    sender.unbounded_send(hello("Ann")).unwrap();
+    let response = client.chat(requests, None).await.unwrap();
+    let mut replies = response.into_body();
+    assert_eq!(
+        replies.message().await.unwrap().unwrap().message,
+        "Hello, Ann!"
+    );
+
+    sender.unbounded_send(hello("Bob")).unwrap();
+    assert_eq!(
+        replies.message().await.unwrap().unwrap().message,
+        "Hello, Bob!"
+    );
+
+    drop(sender);
+    assert!(replies.message().await.unwrap().is_none());
+}
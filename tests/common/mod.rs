@@ -1,3 +1,24 @@
+#![allow(dead_code)]
+
+use bytes::{Buf, Bytes, BytesMut};
+use grpc_project::channel::{BackoffConfig, ConnectivityState};
+use grpc_project::client::{Client, ClientConfig};
+use grpc_project::transport::in_process;
+use grpc_project::{Channel, Error};
+use h2::server::SendResponse;
+use h2::{RecvStream, SendStream};
+use http::{Request, Response};
+use prost::Message;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::timeout;
+
 #[derive(Clone, PartialEq, prost::Message)]
 pub struct TestRequest {
     #[prost(string, tag = "1")]
@@ -9,3 +30,293 @@ pub struct TestResponse {
     #[prost(string, tag = "1")]
     pub message: String,
 }
+
+pub async fn handle_request<B>(_request: Request<B>, respond: SendResponse<Bytes>) {
+    let test_response = TestResponse {
+        message: "test response".to_string(),
+    };
+    send_message(respond, &test_response);
+}
+
+/// Replies with a `TestResponse` carrying the request's own message.
+pub async fn handle_echo(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let test_request: TestRequest = read_message(request.into_body()).await;
+    let test_response = TestResponse {
+        message: test_request.message,
+    };
+    send_message(respond, &test_response);
+}
+
+pub async fn read_message<M: Message + Default>(mut body: RecvStream) -> M {
+    let mut data = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.unwrap();
+        let _ = body.flow_control().release_capacity(chunk.len());
+        data.extend_from_slice(&chunk);
+    }
+    M::decode(&data[5..]).unwrap()
+}
+
+pub fn send_message<M: Message>(mut respond: SendResponse<Bytes>, message: &M) {
+    let response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .header("grpc-encoding", "identity")
+        .header("grpc-status", "0")
+        .body(())
+        .unwrap();
+
+    let mut send_stream = respond.send_response(response, false).unwrap();
+    let _ = send_stream.send_data(frame(message), true);
+}
+
+/// Encodes `message` as one uncompressed gRPC frame.
+pub fn frame<M: Message>(message: &M) -> Bytes {
+    let mut buf = Vec::new();
+    message.encode(&mut buf).unwrap();
+
+    let mut framed_data = Vec::with_capacity(buf.len() + 5);
+    framed_data.push(0); // compression flag
+    framed_data.extend_from_slice(&(buf.len() as u32).to_be_bytes());
+    framed_data.extend_from_slice(&buf);
+    Bytes::from(framed_data)
+}
+
+/// Reads the next message of a streaming request, keeping partial frames in
+/// `buf`; `None` once the client ends the stream.
+pub async fn next_message<M: Message + Default>(
+    body: &mut RecvStream,
+    buf: &mut BytesMut,
+) -> Option<M> {
+    loop {
+        if buf.len() >= 5 {
+            let len = u32::from_be_bytes([buf[1], buf[2], buf[3], buf[4]]) as usize;
+            if buf.len() >= 5 + len {
+                buf.advance(5);
+                return Some(M::decode(buf.split_to(len)).unwrap());
+            }
+        }
+        let chunk = body.data().await?.unwrap();
+        let _ = body.flow_control().release_capacity(chunk.len());
+        buf.extend_from_slice(&chunk);
+    }
+}
+
+/// Sends the headers of a streaming response, whose messages are then sent
+/// as [`frame`]s and which ends with [`send_trailers`].
+pub fn start_response(mut respond: SendResponse<Bytes>) -> SendStream<Bytes> {
+    let response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .body(())
+        .unwrap();
+    respond.send_response(response, false).unwrap()
+}
+
+/// Ends a streaming response with `code`.
+pub fn send_trailers(send_stream: &mut SendStream<Bytes>, code: i32) {
+    let mut trailers = http::HeaderMap::new();
+    trailers.insert("grpc-status", code.into());
+    let _ = send_stream.send_trailers(trailers);
+}
+
+/// A local h2 server that can be stopped, dropping every open connection.
+pub struct TestServer {
+    pub addr: String,
+    pub local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl TestServer {
+    pub async fn start<F, Fut>(handler: F) -> Self
+    where
+        F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self::start_on("127.0.0.1:0".parse().unwrap(), handler).await
+    }
+
+    pub async fn start_on<F, Fut>(addr: SocketAddr, handler: F) -> Self
+    where
+        F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let task = tokio::spawn(async move {
+            // Connections live in the set so aborting this task closes them all.
+            let mut connections = JoinSet::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                connections.spawn(serve_connection(socket, handler.clone()));
+            }
+        });
+
+        Self {
+            addr: format!("http://{}", local_addr),
+            local_addr,
+            task,
+        }
+    }
+
+    pub async fn stop(self) {
+        self.task.abort();
+        let _ = self.task.await;
+    }
+}
+
+/// Serves the h2 streams of one connection with `handler` until it closes.
+pub async fn serve_connection<T, F, Fut>(socket: T, handler: F)
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+    F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut builder = h2::server::Builder::new();
+    builder.max_concurrent_streams(100);
+    let mut connection = match builder.handshake(socket).await {
+        Ok(connection) => connection,
+        Err(_) => return,
+    };
+
+    let mut streams = JoinSet::new();
+    while let Some(request) = connection.accept().await {
+        if let Ok((request, respond)) = request {
+            streams.spawn(handler(request, respond));
+        }
+    }
+}
+
+/// Serves `handler` in process and returns a client connected to it.
+pub fn serve_in_process<F, Fut>(handler: F, config: Option<ClientConfig>) -> Client
+where
+    F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Client::in_process(in_process_server(handler), config)
+}
+
+/// Serves `handler` in process and returns the connector that reaches it.
+pub fn in_process_server<F, Fut>(handler: F) -> in_process::InProcessConnector
+where
+    F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (connector, mut listener) = in_process::pair();
+    tokio::spawn(async move {
+        let mut connections = JoinSet::new();
+        while let Some(stream) = listener.accept().await {
+            connections.spawn(serve_connection(stream, handler.clone()));
+        }
+    });
+    connector
+}
+
+/// Serves every accepted stream with `handler` on an ephemeral port.
+pub async fn serve<F, Fut>(handler: F) -> String
+where
+    F: Fn(Request<RecvStream>, SendResponse<Bytes>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    TestServer::start(handler).await.addr
+}
+
+pub async fn setup_echo_server() -> String {
+    serve(handle_echo).await
+}
+
+/// Sends a trailers-only response carrying `code`, plus any extra headers.
+pub fn send_status(mut respond: SendResponse<Bytes>, code: i32, headers: &[(&str, &str)]) {
+    let mut response = Response::builder()
+        .status(200)
+        .header("content-type", "application/grpc")
+        .header("grpc-status", code.to_string())
+        .header("grpc-message", "test%20failure");
+    for (key, value) in headers {
+        response = response.header(*key, *value);
+    }
+
+    let _ = respond.send_response(response.body(()).unwrap(), true);
+}
+
+pub const UNAVAILABLE: i32 = 14;
+
+/// The `grpc-previous-rpc-attempts` header of every attempt a
+/// [`flaky_server`] has seen.
+pub type Attempts = Arc<Mutex<Vec<Option<String>>>>;
+
+/// Fails the first `failures` calls with `code`, then echoes. Records the
+/// `grpc-previous-rpc-attempts` header of every attempt.
+pub async fn flaky_server(
+    failures: usize,
+    code: i32,
+    headers: &'static [(&'static str, &'static str)],
+) -> (String, Attempts) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let seen = attempts.clone();
+
+    let handler = move |request: Request<RecvStream>, respond: SendResponse<Bytes>| {
+        let calls = calls.clone();
+        let seen = seen.clone();
+        async move {
+            let previous = request
+                .headers()
+                .get("grpc-previous-rpc-attempts")
+                .map(|value| value.to_str().unwrap().to_string());
+            seen.lock().unwrap().push(previous);
+
+            if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                send_status(respond, code, headers);
+            } else {
+                handle_echo(request, respond).await;
+            }
+        }
+    };
+
+    (serve(handler).await, attempts)
+}
+
+/// Reconnects within tens of milliseconds, so tests can watch a channel
+/// recover.
+pub fn fast_backoff_config() -> ClientConfig {
+    ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(200))
+        .connection_backoff(BackoffConfig {
+            base_delay: Duration::from_millis(20),
+            multiplier: 1.6,
+            jitter: 0.2,
+            max_delay: Duration::from_millis(100),
+        })
+        .build()
+        .unwrap()
+}
+
+/// Makes an echo call and returns the echoed message.
+pub async fn echo(client: &Client, message: &str) -> Result<String, Error> {
+    let request = TestRequest {
+        message: message.to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+/// Waits up to five seconds for `channel` to reach `target`.
+pub async fn wait_for_state(channel: &Channel, target: ConnectivityState) {
+    timeout(Duration::from_secs(5), async {
+        let mut state = channel.state();
+        while state != target {
+            state = channel.wait_for_state_change(state).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("channel never reached {}", target));
+}
+
+/// An address nothing listens on, which a server can bind later.
+pub async fn unused_addr() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap()
+}
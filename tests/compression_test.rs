@@ -1,4 +1,6 @@
-use grpc_project::codec::compression::{Compression, GzipCompression, NoCompression};
+use grpc_project::codec::compression::{
+    Compression, CompressionEncoding, DeflateCompression, GzipCompression, NoCompression,
+};
 
 #[test]
 fn test_gzip_compression() {
@@ -37,3 +39,46 @@ fn test_empty_data() {
     let decompressed = compression.decompress(&compressed).unwrap();
     assert_eq!(decompressed, data);
 }
+
+#[test]
+fn test_deflate_compression() {
+    let compression = DeflateCompression;
+    let data = vec![b'd'; 64 * 1024];
+    let compressed = compression.compress(&data).unwrap();
+    assert!(compressed.len() < data.len());
+    assert_eq!(compression.decompress(&compressed).unwrap(), data);
+}
+
+#[test]
+fn test_encoding_names() {
+    for encoding in [
+        CompressionEncoding::None,
+        CompressionEncoding::Gzip,
+        CompressionEncoding::Deflate,
+    ] {
+        assert_eq!(
+            CompressionEncoding::from_name(encoding.as_str()),
+            Some(encoding)
+        );
+    }
+    assert_eq!(CompressionEncoding::from_name("snappy"), None);
+}
+
+#[test]
+fn test_bounded_decompression_stops_past_limit() {
+    let data = vec![0u8; 4 * 1024 * 1024];
+    for compression in [
+        CompressionEncoding::Gzip.compression(),
+        CompressionEncoding::Deflate.compression(),
+    ] {
+        let compressed = compression.compress(&data).unwrap();
+        assert!(compressed.len() < 64 * 1024);
+
+        let bounded = compression.decompress_bounded(&compressed, 1024).unwrap();
+        assert_eq!(bounded.len(), 1025);
+        let whole = compression
+            .decompress_bounded(&compressed, data.len())
+            .unwrap();
+        assert_eq!(whole, data);
+    }
+}
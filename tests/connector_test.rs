@@ -0,0 +1,108 @@
+// tests/connector_test.rs
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use grpc_project::{
+    channel::{Address, ConnectivityState, StaticResolver},
+    client::{Client, ClientConfig},
+    transport::{BoxedIo, Connector, DefaultConnector},
+    Channel, Code, Error, Result,
+};
+
+mod common;
+use common::{echo, handle_echo, serve_connection, setup_echo_server};
+
+/// Counts dials and hands them to the default connector.
+#[derive(Debug, Default)]
+struct CountingConnector {
+    connects: AtomicUsize,
+}
+
+impl Connector for CountingConnector {
+    fn connect(&self, address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        self.connects.fetch_add(1, Ordering::SeqCst);
+        DefaultConnector::default().connect(address)
+    }
+}
+
+/// Serves every dial from an in-memory echo server.
+#[derive(Debug)]
+struct DuplexConnector;
+
+impl Connector for DuplexConnector {
+    fn connect(&self, address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        assert_eq!(*address, Address::Named("memory".to_string()));
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(serve_connection(server, handle_echo));
+        Box::pin(async move { Ok(Box::new(client) as BoxedIo) })
+    }
+}
+
+#[tokio::test]
+async fn test_custom_connector_is_used_for_dialing() {
+    let addr = setup_echo_server().await;
+    let connector = Arc::new(CountingConnector::default());
+    let config = ClientConfig::builder()
+        .connector(connector.clone())
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "counted").await.unwrap(), "counted");
+    assert_eq!(connector.connects.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_connector_for_named_address() {
+    let config = ClientConfig::builder()
+        .connector(DuplexConnector)
+        .build()
+        .unwrap();
+    let resolver = StaticResolver::new([Address::Named("memory".to_string())]);
+    let channel = Channel::resolve_with("memory:///echo", &config, Box::new(resolver)).unwrap();
+    let client = Client::from_channel(channel, Some(config));
+
+    assert_eq!(echo(&client, "in memory").await.unwrap(), "in memory");
+}
+
+#[tokio::test]
+async fn test_from_stream() {
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    let server = tokio::spawn(serve_connection(server_io, handle_echo));
+
+    let client = Client::from_stream(client_io, None).await.unwrap();
+    assert_eq!(echo(&client, "duplex").await.unwrap(), "duplex");
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test]
+async fn test_from_stream_does_not_reconnect() {
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    let server = tokio::spawn(serve_connection(server_io, handle_echo));
+
+    let channel = Channel::from_stream(client_io, &ClientConfig::default())
+        .await
+        .unwrap();
+    let client = Client::from_channel(channel.clone(), None);
+    assert_eq!(echo(&client, "once").await.unwrap(), "once");
+
+    server.abort();
+    let _ = server.await;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        let mut state = channel.state();
+        while state != ConnectivityState::TransientFailure {
+            state = channel.wait_for_state_change(state).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    match echo(&client, "again").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected Unavailable, got {:?}", other),
+    }
+}
@@ -0,0 +1,170 @@
+// tests/credentials_test.rs
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use grpc_project::{
+    channel::{Address, StaticResolver},
+    client::{CallOptions, Client, ClientConfig, RefreshingToken, StaticToken, Token},
+    transport::{BoxedIo, Connector},
+    Channel, Code, Error, Result,
+};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+
+mod common;
+use common::{send_message, serve_connection, serve_in_process, TestRequest, TestResponse};
+
+/// Replies with the request's `authorization` header.
+async fn handle_authorization(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let authorization = request
+        .headers()
+        .get("authorization")
+        .map_or("-", |value| value.to_str().unwrap())
+        .to_string();
+    send_message(
+        respond,
+        &TestResponse {
+            message: authorization,
+        },
+    );
+}
+
+/// Serves every dial in memory and reports its streams as encrypted.
+#[derive(Debug)]
+struct SecureConnector;
+
+impl Connector for SecureConnector {
+    fn connect(&self, _address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(serve_connection(server, handle_authorization));
+        Box::pin(async move { Ok(Box::new(client) as BoxedIo) })
+    }
+
+    fn is_secure(&self) -> bool {
+        true
+    }
+}
+
+fn secure_client() -> Client {
+    let config = ClientConfig::builder()
+        .connector(SecureConnector)
+        .build()
+        .unwrap();
+    let resolver = StaticResolver::new([Address::Named("memory".to_string())]);
+    let channel =
+        Channel::resolve_with("dns:///api.example.com:443", &config, Box::new(resolver)).unwrap();
+    Client::from_channel(channel, Some(config))
+}
+
+async fn authorization(client: &Client, options: CallOptions) -> Result<String> {
+    let request = TestRequest {
+        message: "who am i".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Whoami", request, options)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+#[tokio::test]
+async fn test_static_token() {
+    let client = secure_client();
+    let options = CallOptions::new().with_credentials(StaticToken::new("s3cret"));
+    assert_eq!(
+        authorization(&client, options).await.unwrap(),
+        "Bearer s3cret"
+    );
+    assert!(!format!("{:?}", StaticToken::new("s3cret")).contains("s3cret"));
+}
+
+#[tokio::test]
+async fn test_credentials_refused_on_insecure_connection() {
+    let client = serve_in_process(handle_authorization, None);
+    let options = CallOptions::new().with_credentials(StaticToken::new("s3cret"));
+    match authorization(&client, options).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unauthenticated),
+        other => panic!("expected Unauthenticated, got {:?}", other),
+    }
+
+    // Calls without credentials are unaffected.
+    assert_eq!(
+        authorization(&client, CallOptions::new()).await.unwrap(),
+        "-"
+    );
+
+    let config = ClientConfig::builder()
+        .allow_insecure_credentials(true)
+        .build()
+        .unwrap();
+    let client = serve_in_process(handle_authorization, Some(config));
+    let options = CallOptions::new().with_credentials(StaticToken::new("s3cret"));
+    assert_eq!(
+        authorization(&client, options).await.unwrap(),
+        "Bearer s3cret"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_refreshing_token_refreshes_after_expiry() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let counter = fetches.clone();
+    let credentials = Arc::new(
+        RefreshingToken::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                Ok(Token::expiring_in(
+                    format!("jwt-{}", n),
+                    Duration::from_secs(60),
+                ))
+            }
+        })
+        .with_refresh_margin(Duration::from_secs(5)),
+    );
+    let client = secure_client();
+    let options = || CallOptions::new().with_credentials(credentials.clone());
+
+    for _ in 0..3 {
+        assert_eq!(
+            authorization(&client, options()).await.unwrap(),
+            "Bearer jwt-1"
+        );
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    tokio::time::advance(Duration::from_secs(56)).await;
+    assert_eq!(
+        authorization(&client, options()).await.unwrap(),
+        "Bearer jwt-2"
+    );
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_refresh_failure_fails_call_and_is_retried() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let counter = fetches.clone();
+    let credentials = Arc::new(RefreshingToken::new(move || {
+        let first = counter.fetch_add(1, Ordering::SeqCst) == 0;
+        async move {
+            match first {
+                true => Err(Error::Protocol("token endpoint down".to_string())),
+                false => Ok(Token::expiring_in("jwt", Duration::from_secs(60))),
+            }
+        }
+    }));
+    let client = secure_client();
+    let options = || CallOptions::new().with_credentials(credentials.clone());
+
+    match authorization(&client, options()).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected Unavailable, got {:?}", other),
+    }
+    assert_eq!(
+        authorization(&client, options()).await.unwrap(),
+        "Bearer jwt"
+    );
+}
@@ -7,7 +7,7 @@ fn test_frame_creation() {
     let data = Bytes::from("test");
     let frame = Frame::new(data.clone(), false);
     assert_eq!(frame.data, data);
-    assert_eq!(frame.header.compressed, false);
+    assert!(!frame.header.compressed);
     assert_eq!(frame.len(), 4);
 }
 
@@ -21,7 +21,7 @@ fn test_frame_encode_decode() {
     let decoded = Frame::decode(&mut buf).unwrap();
 
     assert_eq!(decoded.data, data);
-    assert_eq!(decoded.header.compressed, false);
+    assert!(!decoded.header.compressed);
     assert_eq!(decoded.len(), frame.len());
 }
 
@@ -54,5 +54,5 @@ fn test_compressed_frame() {
     let decoded = Frame::decode(&mut buf).unwrap();
 
     assert_eq!(decoded.data, data);
-    assert_eq!(decoded.header.compressed, true);
+    assert!(decoded.header.compressed);
 }
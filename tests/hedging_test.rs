@@ -0,0 +1,185 @@
+// tests/hedging_test.rs
+mod common;
+
+use bytes::Bytes;
+use common::*;
+use grpc_project::client::{ClientConfig, ClientConfigBuilder, HedgingPolicy};
+use grpc_project::{Client, Code, Error};
+use h2::server::SendResponse;
+use h2::{Reason, RecvStream};
+use http::Request;
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const METHOD: &str = "/test.service/Lookup";
+
+fn hedging_config(policy: HedgingPolicy) -> ClientConfig {
+    let mut config = ClientConfig::default();
+    config.hedging_policies.insert(METHOD.to_string(), policy);
+    config
+}
+
+async fn call(client: &Client, method: &str) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "hedge".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>(method, request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+/// The first call stalls until the client resets it; every later call is
+/// answered immediately. Records how the stalled stream ended.
+async fn slow_first_server() -> (String, Arc<AtomicUsize>, Arc<Mutex<Option<Reason>>>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let reset = Arc::new(Mutex::new(None));
+    let counter = calls.clone();
+    let seen = reset.clone();
+
+    let handler = move |request: Request<RecvStream>, mut respond: SendResponse<Bytes>| {
+        let counter = counter.clone();
+        let seen = seen.clone();
+        async move {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                let waited = tokio::time::timeout(
+                    Duration::from_secs(5),
+                    poll_fn(|cx| respond.poll_reset(cx)),
+                )
+                .await;
+                if let Ok(Ok(reason)) = waited {
+                    *seen.lock().unwrap() = Some(reason);
+                }
+            } else {
+                handle_echo(request, respond).await;
+            }
+        }
+    };
+
+    (serve(handler).await, calls, reset)
+}
+
+#[tokio::test]
+async fn test_hedge_wins_over_slow_attempt() {
+    let (addr, calls, reset) = slow_first_server().await;
+    let config = hedging_config(HedgingPolicy {
+        max_attempts: 3,
+        hedging_delay: Duration::from_millis(50),
+        non_fatal_status_codes: vec![],
+    });
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    let start = Instant::now();
+    assert_eq!(call(&client, METHOD).await.unwrap(), "hedge");
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // The losing attempt is cancelled with RST_STREAM.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(*reset.lock().unwrap(), Some(Reason::CANCEL));
+}
+
+#[tokio::test]
+async fn test_unconfigured_method_is_not_hedged() {
+    let (addr, calls, _) = slow_first_server().await;
+    let config = ClientConfigBuilder::from_config(hedging_config(HedgingPolicy {
+        hedging_delay: Duration::from_millis(50),
+        ..HedgingPolicy::default()
+    }))
+    .timeout(Duration::from_millis(300))
+    .build()
+    .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    match call(&client, "/test.service/Other").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::DeadlineExceeded),
+        other => panic!("expected deadline, got {:?}", other),
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+/// Answers every call with `code` and counts them.
+async fn failing_server(code: i32) -> (String, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let addr = serve(move |_request, respond| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            send_status(respond, code, &[]);
+        }
+    })
+    .await;
+    (addr, calls)
+}
+
+#[tokio::test]
+async fn test_non_fatal_failure_hedges_immediately() {
+    let (addr, calls) = failing_server(14).await;
+    let config = hedging_config(HedgingPolicy {
+        max_attempts: 4,
+        hedging_delay: Duration::from_secs(10),
+        non_fatal_status_codes: vec![Code::Unavailable],
+    });
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    let start = Instant::now();
+    match call(&client, METHOD).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected status error, got {:?}", other),
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_fatal_failure_stops_hedging() {
+    let (addr, calls) = failing_server(13).await;
+    let config = hedging_config(HedgingPolicy {
+        max_attempts: 4,
+        hedging_delay: Duration::from_millis(200),
+        non_fatal_status_codes: vec![Code::Unavailable],
+    });
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    match call(&client, METHOD).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Internal),
+        other => panic!("expected status error, got {:?}", other),
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_hedges_carry_previous_attempts() {
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let seen = attempts.clone();
+    let addr = serve(move |request: Request<RecvStream>, respond| {
+        let seen = seen.clone();
+        async move {
+            let previous = request
+                .headers()
+                .get("grpc-previous-rpc-attempts")
+                .map(|value| value.to_str().unwrap().to_string());
+            seen.lock().unwrap().push(previous);
+            send_status(respond, 14, &[]);
+        }
+    })
+    .await;
+    let config = hedging_config(HedgingPolicy {
+        max_attempts: 3,
+        hedging_delay: Duration::from_millis(10),
+        non_fatal_status_codes: vec![Code::Unavailable],
+    });
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    assert!(call(&client, METHOD).await.is_err());
+    let mut attempts = attempts.lock().unwrap().clone();
+    attempts.sort();
+    assert_eq!(
+        attempts,
+        vec![None, Some("1".to_string()), Some("2".to_string())]
+    );
+}
@@ -0,0 +1,77 @@
+// tests/http2_config_test.rs
+use std::time::Duration;
+
+use grpc_project::{
+    client::{Client, ClientConfig, MAX_WINDOW_SIZE},
+    Error, DEFAULT_WINDOW_SIZE,
+};
+
+mod common;
+use common::{echo, handle_echo, serve_connection, serve_in_process, setup_echo_server};
+
+#[test]
+fn test_default_config_is_valid() {
+    ClientConfig::default().validate().unwrap();
+}
+
+#[test]
+fn test_out_of_range_settings_are_rejected() {
+    let invalid = [
+        ClientConfig::builder().http2_initial_stream_window_size(MAX_WINDOW_SIZE + 1),
+        ClientConfig::builder().http2_initial_connection_window_size(DEFAULT_WINDOW_SIZE - 1),
+        ClientConfig::builder().http2_max_frame_size(1024),
+        ClientConfig::builder().http2_max_frame_size(1 << 24),
+        ClientConfig::builder().max_concurrent_streams(0),
+        ClientConfig::builder().tcp_keepalive(Duration::ZERO),
+    ];
+
+    for builder in invalid {
+        assert!(
+            matches!(builder.clone().build(), Err(Error::Config(_))),
+            "accepted {:?}",
+            builder
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_connect_rejects_invalid_config() {
+    let mut config = ClientConfig::default();
+    config.http2_max_frame_size = 0;
+
+    let result = Client::connect("http://127.0.0.1:1", Some(config.clone())).await;
+    assert!(matches!(result, Err(Error::Config(_))));
+
+    // Channels built on a caller's stream are checked at handshake time.
+    let (client_io, server_io) = tokio::io::duplex(1024);
+    tokio::spawn(serve_connection(server_io, handle_echo));
+    let result = Client::from_stream(client_io, Some(config)).await;
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_large_message_with_small_windows() {
+    let config = ClientConfig::builder()
+        .http2_initial_stream_window_size(DEFAULT_WINDOW_SIZE)
+        .http2_initial_connection_window_size(DEFAULT_WINDOW_SIZE)
+        .http2_max_frame_size(32 * 1024)
+        .build()
+        .unwrap();
+    let client = serve_in_process(handle_echo, Some(config));
+
+    let message = "x".repeat(1024 * 1024);
+    assert_eq!(echo(&client, &message).await.unwrap(), message);
+}
+
+#[tokio::test]
+async fn test_tcp_options() {
+    let addr = setup_echo_server().await;
+    let config = ClientConfig::builder()
+        .tcp_nodelay(false)
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "tcp").await.unwrap(), "tcp");
+}
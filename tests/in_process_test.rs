@@ -0,0 +1,79 @@
+// tests/in_process_test.rs
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_project::{
+    channel::ConnectivityState,
+    client::{Client, ClientConfig},
+    transport::in_process,
+    Channel, Code, Error,
+};
+
+mod common;
+use common::{echo, handle_echo, send_message, serve_in_process, TestResponse};
+
+#[tokio::test(start_paused = true)]
+async fn test_unary_in_process() {
+    let client = serve_in_process(handle_echo, None);
+    assert_eq!(echo(&client, "no sockets").await.unwrap(), "no sockets");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrent_calls_share_one_connection() {
+    let (connector, mut listener) = in_process::pair();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+    tokio::spawn(async move {
+        while let Some(stream) = listener.accept().await {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(common::serve_connection(stream, handle_echo));
+        }
+    });
+    let client = Client::in_process(connector, None);
+
+    let calls = (0..10).map(|i| {
+        let client = client.clone();
+        async move { echo(&client, &i.to_string()).await.unwrap() }
+    });
+    let replies = futures::future::join_all(calls).await;
+
+    assert_eq!(replies, (0..10).map(|i| i.to_string()).collect::<Vec<_>>());
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_deadline_on_paused_clock() {
+    // The server takes a minute; the paused clock skips ahead instantly.
+    let slow = |_request, respond| async move {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        let response = TestResponse {
+            message: "late".to_string(),
+        };
+        send_message(respond, &response);
+    };
+    let config = ClientConfig::builder()
+        .timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    let client = serve_in_process(slow, Some(config));
+
+    match echo(&client, "hurry").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::DeadlineExceeded),
+        other => panic!("expected DeadlineExceeded, got {:?}", other),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dropped_listener_is_unavailable() {
+    let (connector, listener) = in_process::pair();
+    drop(listener);
+    let channel = Channel::in_process(connector, &ClientConfig::default());
+    assert_eq!(channel.state(), ConnectivityState::Idle);
+
+    let client = Client::from_channel(channel, None);
+    match echo(&client, "anyone?").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected Unavailable, got {:?}", other),
+    }
+}
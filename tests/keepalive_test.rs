@@ -0,0 +1,155 @@
+// tests/keepalive_test.rs
+use futures::future;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+
+use grpc_project::{
+    channel::{BackoffConfig, ConnectivityState},
+    client::{ClientConfig, ClientConfigBuilder},
+    Channel,
+};
+
+mod common;
+use common::{handle_echo, TestServer};
+
+fn keepalive_config() -> ClientConfig {
+    ClientConfig::builder()
+        .enable_http2_keepalive(true)
+        .http2_keepalive_interval(Duration::from_millis(50))
+        .http2_keepalive_timeout(Duration::from_millis(50))
+        .http2_keepalive_while_idle(true)
+        .connect_timeout(Duration::from_millis(200))
+        .connection_backoff(BackoffConfig {
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+/// Completes the HTTP/2 handshake and then stops reading, so PINGs are
+/// never acknowledged.
+async fn start_frozen_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let _connection = h2::server::handshake(socket).await;
+                future::pending::<()>().await;
+            });
+        }
+    });
+
+    addr
+}
+
+/// Answers every connection with GOAWAY(ENHANCE_YOUR_CALM, "too_many_pings").
+async fn start_calm_down_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut preface = [0u8; 24];
+                if socket.read_exact(&mut preface).await.is_err() {
+                    return;
+                }
+
+                let debug_data = b"too_many_pings";
+                let mut frames = Vec::new();
+                // Empty SETTINGS frame.
+                frames.extend_from_slice(&[0, 0, 0, 0x4, 0, 0, 0, 0, 0]);
+                // GOAWAY: last stream 0, ENHANCE_YOUR_CALM (0xb), debug data.
+                let len = (8 + debug_data.len()) as u8;
+                frames.extend_from_slice(&[0, 0, len, 0x7, 0, 0, 0, 0, 0]);
+                frames.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0xb]);
+                frames.extend_from_slice(debug_data);
+                let _ = socket.write_all(&frames).await;
+
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = socket.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    addr
+}
+
+#[tokio::test]
+async fn test_unacknowledged_ping_drops_connection() {
+    let addr = start_frozen_server().await;
+    let channel = Channel::connect(&addr, &keepalive_config()).await.unwrap();
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+
+    let next = timeout(
+        Duration::from_secs(5),
+        channel.wait_for_state_change(ConnectivityState::Ready),
+    )
+    .await
+    .expect("dead connection was never detected");
+    assert_ne!(next, ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_no_idle_pings_unless_enabled() {
+    let addr = start_frozen_server().await;
+    let config = ClientConfigBuilder::from_config(keepalive_config())
+        .http2_keepalive_while_idle(false)
+        .build()
+        .unwrap();
+    let channel = Channel::connect(&addr, &config).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_acknowledged_pings_keep_connection() {
+    let server = TestServer::start(handle_echo).await;
+    let channel = Channel::connect(&server.addr, &keepalive_config())
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_keepalive_disabled() {
+    let server = TestServer::start(handle_echo).await;
+    let config = ClientConfigBuilder::from_config(keepalive_config())
+        .enable_http2_keepalive(false)
+        .build()
+        .unwrap();
+    let channel = Channel::connect(&server.addr, &config).await.unwrap();
+
+    assert_eq!(channel.keepalive_interval(), None);
+}
+
+#[tokio::test]
+async fn test_too_many_pings_backs_off_interval() {
+    let addr = start_calm_down_server().await;
+    let channel = Channel::connect(&addr, &keepalive_config()).await.unwrap();
+    assert_eq!(
+        channel.keepalive_interval(),
+        Some(Duration::from_millis(50))
+    );
+
+    timeout(Duration::from_secs(5), async {
+        while channel.keepalive_interval() < Some(Duration::from_millis(100)) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("keepalive interval never increased");
+}
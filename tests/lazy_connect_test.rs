@@ -0,0 +1,99 @@
+// tests/lazy_connect_test.rs
+use std::time::Duration;
+
+use grpc_project::{
+    channel::{BackoffConfig, ConnectivityState},
+    client::{Client, ClientConfig, ClientConfigBuilder},
+    Channel, Code, Error,
+};
+
+mod common;
+use common::{echo, handle_echo, unused_addr, wait_for_state, TestServer};
+
+fn test_config() -> ClientConfig {
+    ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(200))
+        .connection_backoff(BackoffConfig {
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_lazy_connect_before_server_starts() {
+    let addr = unused_addr().await;
+    let channel = Channel::connect_lazy(format!("http://{}", addr), &test_config()).unwrap();
+    assert_eq!(channel.state(), ConnectivityState::Idle);
+
+    let _server = TestServer::start_on(addr, handle_echo).await;
+    let client = Client::with_service(channel.clone(), Some(test_config()));
+
+    assert_eq!(echo(&client, "hello").await.unwrap(), "hello");
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_lazy_client() {
+    let server = TestServer::start(handle_echo).await;
+    let client = Client::connect_lazy(&server.addr, Some(test_config())).unwrap();
+
+    assert_eq!(echo(&client, "lazy").await.unwrap(), "lazy");
+}
+
+#[tokio::test]
+async fn test_lazy_connect_rejects_invalid_uri() {
+    let result = Channel::connect_lazy("invalid-uri", &test_config());
+    assert!(matches!(result, Err(Error::Protocol(_))));
+}
+
+#[tokio::test]
+async fn test_lazy_call_without_server_is_unavailable() {
+    let addr = unused_addr().await;
+    let client = Client::connect_lazy(format!("http://{}", addr), Some(test_config())).unwrap();
+
+    let result = echo(&client, "nobody home").await;
+    assert!(
+        matches!(&result, Err(Error::Status(status)) if status.code() == Code::Unavailable),
+        "Expected Unavailable, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_idle_connection_is_closed_and_reopened() {
+    let server = TestServer::start(handle_echo).await;
+    let config = ClientConfigBuilder::from_config(test_config())
+        .idle_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let channel = Channel::connect(&server.addr, &config).await.unwrap();
+    let client = Client::with_service(channel.clone(), Some(config));
+    assert_eq!(echo(&client, "first").await.unwrap(), "first");
+
+    wait_for_state(&channel, ConnectivityState::Idle).await;
+
+    assert_eq!(echo(&client, "second").await.unwrap(), "second");
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_busy_connection_is_not_idle() {
+    let server = TestServer::start(handle_echo).await;
+    let config = ClientConfigBuilder::from_config(test_config())
+        .idle_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let channel = Channel::connect(&server.addr, &config).await.unwrap();
+    let client = Client::with_service(channel.clone(), Some(config));
+
+    for i in 0..10 {
+        assert_eq!(echo(&client, &i.to_string()).await.unwrap(), i.to_string());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(channel.state(), ConnectivityState::Ready);
+    }
+}
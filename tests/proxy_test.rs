@@ -0,0 +1,247 @@
+// tests/proxy_test.rs
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use grpc_project::{
+    channel::Address,
+    client::{Client, ClientConfig},
+    transport::{BoxedIo, Connector, DefaultConnector, ProxyConfig},
+    Channel, Error, Result,
+};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+use common::{echo, setup_echo_server};
+
+/// A stand-in HTTP forward proxy that records each CONNECT request head.
+struct TestProxy {
+    uri: String,
+    requests: Arc<Mutex<Vec<Vec<String>>>>,
+}
+
+impl TestProxy {
+    /// Starts a proxy that tunnels when the request carries
+    /// `required_auth` (if any) and answers 407 otherwise.
+    async fn start(required_auth: Option<&'static str>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let mut socket = BufReader::new(socket);
+                    let mut head = Vec::new();
+                    loop {
+                        let mut line = String::new();
+                        socket.read_line(&mut line).await.unwrap();
+                        let line = line.trim_end().to_string();
+                        if line.is_empty() {
+                            break;
+                        }
+                        head.push(line);
+                    }
+                    recorded.lock().unwrap().push(head.clone());
+
+                    let authorized = required_auth.is_none_or(|auth| {
+                        head.iter()
+                            .any(|line| *line == format!("Proxy-Authorization: {}", auth))
+                    });
+                    if !authorized {
+                        let response = "HTTP/1.1 407 Proxy Authentication Required\r\n\r\n";
+                        let _ = socket.write_all(response.as_bytes()).await;
+                        return;
+                    }
+
+                    // Only the proxy can resolve `grpc.invalid`.
+                    let target = head[0].split_whitespace().nth(1).unwrap();
+                    let target = target.replace("grpc.invalid", "127.0.0.1");
+                    let mut upstream = TcpStream::connect(target).await.unwrap();
+                    socket
+                        .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                        .await
+                        .unwrap();
+                    let _ = tokio::io::copy_bidirectional(&mut socket, &mut upstream).await;
+                });
+            }
+        });
+
+        Self { uri, requests }
+    }
+
+    fn requests(&self) -> Vec<Vec<String>> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[tokio::test]
+async fn test_calls_tunnel_through_proxy() {
+    let addr = setup_echo_server().await;
+    let proxy = TestProxy::start(None).await;
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri(proxy.uri.clone()))
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "tunnelled").await.unwrap(), "tunnelled");
+
+    let target = addr.trim_start_matches("http://");
+    let requests = proxy.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0][0], format!("CONNECT {} HTTP/1.1", target));
+    assert!(requests[0].contains(&format!("Host: {}", target)));
+}
+
+#[tokio::test]
+async fn test_host_names_are_resolved_by_the_proxy() {
+    let addr = setup_echo_server().await;
+    let port = addr.rsplit(':').next().unwrap();
+    let proxy = TestProxy::start(None).await;
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri(proxy.uri.clone()))
+        .build()
+        .unwrap();
+
+    let target = format!("grpc.invalid:{}", port);
+    let client = Client::connect(format!("http://{}", target), Some(config))
+        .await
+        .unwrap();
+    assert_eq!(echo(&client, "by name").await.unwrap(), "by name");
+    assert_eq!(
+        proxy.requests()[0][0],
+        format!("CONNECT {} HTTP/1.1", target)
+    );
+}
+
+#[tokio::test]
+async fn test_proxy_basic_auth() {
+    let addr = setup_echo_server().await;
+    // "user:secret"
+    let proxy = TestProxy::start(Some("Basic dXNlcjpzZWNyZXQ=")).await;
+    let uri = proxy.uri.replace("http://", "http://user:secret@");
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri(uri))
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "authorized").await.unwrap(), "authorized");
+}
+
+#[tokio::test]
+async fn test_proxy_credentials_are_percent_decoded() {
+    let addr = setup_echo_server().await;
+    // "user@corp:p:ss"
+    let proxy = TestProxy::start(Some("Basic dXNlckBjb3JwOnA6c3M=")).await;
+    let uri = proxy.uri.replace("http://", "http://user%40corp:p%3Ass@");
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri(uri))
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "decoded").await.unwrap(), "decoded");
+}
+
+/// Sends every dial to `to`, claiming the connections are secure as a TLS
+/// connector would.
+#[derive(Debug)]
+struct RedirectConnector {
+    to: SocketAddr,
+    connects: Arc<AtomicUsize>,
+}
+
+impl Connector for RedirectConnector {
+    fn connect(&self, _address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        self.connects.fetch_add(1, Ordering::SeqCst);
+        DefaultConnector::default().connect(&Address::Tcp(self.to))
+    }
+
+    fn is_secure(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_custom_connector_ignores_the_environment_proxy() {
+    let addr = setup_echo_server().await;
+    let proxy = TestProxy::start(None).await;
+    // No other test here takes the proxy from the environment.
+    std::env::set_var("grpc_proxy", &proxy.uri);
+
+    let connects = Arc::new(AtomicUsize::new(0));
+    let connector = RedirectConnector {
+        to: addr.trim_start_matches("http://").parse().unwrap(),
+        connects: connects.clone(),
+    };
+    let config = ClientConfig::builder()
+        .connector(connector)
+        .build()
+        .unwrap();
+
+    // Not a loopback address, so the environment's proxy would apply.
+    let channel = Channel::connect("http://192.0.2.1:50051", &config)
+        .await
+        .unwrap();
+    assert!(channel.is_secure());
+    let client = Client::from_channel(channel, None);
+    assert_eq!(echo(&client, "direct").await.unwrap(), "direct");
+    assert_eq!(connects.load(Ordering::SeqCst), 1);
+    assert!(proxy.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_explicit_proxy_with_custom_connector_is_rejected() {
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri("http://proxy.local:3128".to_string()))
+        .connector(DefaultConnector::default())
+        .build()
+        .unwrap();
+
+    let result = Channel::connect_lazy("http://127.0.0.1:50051", &config);
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_proxy_rejection_fails_connect() {
+    let addr = setup_echo_server().await;
+    let proxy = TestProxy::start(Some("Basic dXNlcjpzZWNyZXQ=")).await;
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri(proxy.uri.clone()))
+        .build()
+        .unwrap();
+
+    match Client::connect(&addr, Some(config)).await {
+        Err(Error::Protocol(message)) => assert!(message.contains("407"), "{}", message),
+        other => panic!("expected a CONNECT failure, got {:?}", other.err()),
+    }
+}
+
+#[tokio::test]
+async fn test_disabled_proxy_connects_directly() {
+    let addr = setup_echo_server().await;
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Disabled)
+        .build()
+        .unwrap();
+
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+    assert_eq!(echo(&client, "direct").await.unwrap(), "direct");
+}
+
+#[tokio::test]
+async fn test_invalid_proxy_uri_is_rejected() {
+    let config = ClientConfig::builder()
+        .proxy(ProxyConfig::Uri("https://proxy.local:3128".to_string()))
+        .build()
+        .unwrap();
+
+    let result = Client::connect_lazy("http://127.0.0.1:50051", Some(config));
+    assert!(matches!(result, Err(Error::Config(_))));
+}
@@ -0,0 +1,113 @@
+// tests/reconnect_test.rs
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+
+use grpc_project::{channel::ConnectivityState, client::Client, Channel, Code, Error};
+
+mod common;
+use common::{echo, fast_backoff_config, handle_echo, wait_for_state, TestServer};
+
+#[tokio::test]
+async fn test_connected_channel_is_ready() {
+    let server = TestServer::start(handle_echo).await;
+    let channel = Channel::connect(&server.addr, &fast_backoff_config())
+        .await
+        .unwrap();
+
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+}
+
+#[tokio::test]
+async fn test_reconnects_after_server_restart() {
+    let server = TestServer::start(handle_echo).await;
+    let local_addr = server.local_addr;
+    let config = fast_backoff_config();
+
+    let channel = Channel::connect(&server.addr, &config).await.unwrap();
+    let client = Client::with_service(channel.clone(), Some(config));
+    assert_eq!(echo(&client, "before").await.unwrap(), "before");
+
+    server.stop().await;
+    wait_for_state(&channel, ConnectivityState::TransientFailure).await;
+
+    let result = echo(&client, "during").await;
+    assert!(
+        matches!(&result, Err(Error::Status(status)) if status.code() == Code::Unavailable),
+        "Expected Unavailable, got {:?}",
+        result
+    );
+
+    let _server = TestServer::start_on(local_addr, handle_echo).await;
+    wait_for_state(&channel, ConnectivityState::Ready).await;
+    assert_eq!(echo(&client, "after").await.unwrap(), "after");
+}
+
+#[tokio::test]
+async fn test_wait_for_state_change_reports_connection_loss() {
+    let server = TestServer::start(handle_echo).await;
+    let channel = Channel::connect(&server.addr, &fast_backoff_config())
+        .await
+        .unwrap();
+
+    server.stop().await;
+    let next = timeout(
+        Duration::from_secs(5),
+        channel.wait_for_state_change(ConnectivityState::Ready),
+    )
+    .await
+    .unwrap();
+    assert_ne!(next, ConnectivityState::Ready);
+}
+
+// Multi-threaded so a redial loop without backoff fails the test instead of
+// starving it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_peer_that_hangs_up_is_redialed_with_backoff() {
+    // Accepts every connection and closes it before any HTTP/2 is spoken.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let dials = Arc::new(Mutex::new(Vec::new()));
+    let accepted = dials.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            accepted.lock().unwrap().push(Instant::now());
+            drop(socket);
+        }
+    });
+
+    let config = fast_backoff_config();
+    let channel = Channel::connect_lazy(&addr, &config).unwrap();
+    let states = tokio::spawn({
+        let channel = channel.clone();
+        async move {
+            let mut states = Vec::new();
+            let mut state = channel.state();
+            while state != ConnectivityState::Shutdown {
+                state = channel.wait_for_state_change(state).await;
+                states.push(state);
+            }
+            states
+        }
+    });
+
+    let client = Client::with_service(channel.clone(), Some(config));
+    assert!(echo(&client, "hello").await.is_err());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    channel.shutdown().await;
+
+    let states = states.await.unwrap();
+    assert!(
+        !states.contains(&ConnectivityState::Ready),
+        "went Ready without the server's SETTINGS"
+    );
+
+    // Backoff starts at 20ms with 20% jitter and caps at 100ms.
+    let dials = dials.lock().unwrap();
+    assert!((3..=15).contains(&dials.len()), "{} dials", dials.len());
+    for pair in dials.windows(2) {
+        let gap = pair[1] - pair[0];
+        assert!(gap >= Duration::from_millis(15), "redialed after {:?}", gap);
+    }
+}
@@ -0,0 +1,211 @@
+// tests/request_headers_test.rs
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use grpc_project::{
+    channel::{Address, StaticResolver},
+    client::{CallOptions, Client, ClientConfig},
+    transport::{Body, BoxedIo, Connector},
+    Channel, Error, Result,
+};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+use tower::ServiceExt;
+
+mod common;
+use common::{send_message, serve, serve_connection, TestRequest, TestResponse};
+
+const USER_AGENT: &str = concat!("grpc-rust-grpc_project/", env!("CARGO_PKG_VERSION"));
+
+/// Replies with the request's scheme, authority and user agent.
+async fn handle_describe(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let uri = request.uri();
+    let description = format!(
+        "{} {} {}",
+        uri.scheme_str().unwrap_or("-"),
+        uri.authority().map_or("-", |authority| authority.as_str()),
+        request
+            .headers()
+            .get("user-agent")
+            .map_or("-", |value| value.to_str().unwrap()),
+    );
+    send_message(
+        respond,
+        &TestResponse {
+            message: description,
+        },
+    );
+}
+
+async fn describe(client: &Client, options: CallOptions) -> String {
+    let request = TestRequest {
+        message: "describe".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Describe", request, options)
+        .await
+        .unwrap()
+        .into_body()
+        .message
+}
+
+/// Serves every dial in memory and reports its streams as encrypted.
+#[derive(Debug)]
+struct SecureConnector;
+
+impl Connector for SecureConnector {
+    fn connect(&self, _address: &Address) -> BoxFuture<'static, Result<BoxedIo>> {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(serve_connection(server, handle_describe));
+        Box::pin(async move { Ok(Box::new(client) as BoxedIo) })
+    }
+
+    fn is_secure(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_defaults_come_from_target() {
+    let addr = serve(handle_describe).await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let authority = addr.strip_prefix("http://").unwrap();
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("http {} {}", authority, USER_AGENT)
+    );
+}
+
+#[tokio::test]
+async fn test_configured_authority_and_call_override() {
+    let addr = serve(handle_describe).await;
+    let config = ClientConfig::builder()
+        .authority("api.example.com".to_string())
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("http api.example.com {}", USER_AGENT)
+    );
+
+    // A per-call authority beats the configured one.
+    let options = CallOptions::new().with_authority("canary.example.com");
+    assert_eq!(
+        describe(&client, options).await,
+        format!("http canary.example.com {}", USER_AGENT)
+    );
+}
+
+#[tokio::test]
+async fn test_secure_connector_uses_https() {
+    let config = ClientConfig::builder()
+        .connector(SecureConnector)
+        .build()
+        .unwrap();
+    let resolver = StaticResolver::new([Address::Named("memory".to_string())]);
+    let channel =
+        Channel::resolve_with("dns:///secure.example.com:443", &config, Box::new(resolver))
+            .unwrap();
+    let client = Client::from_channel(channel, Some(config));
+
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("https secure.example.com:443 {}", USER_AGENT)
+    );
+}
+
+#[tokio::test]
+async fn test_address_list_uses_first_address() {
+    let addr = serve(handle_describe).await;
+    let first = addr.strip_prefix("http://").unwrap();
+    let target = format!("ipv4:{},127.0.0.1:1", first);
+    let client = Client::connect(&target, None).await.unwrap();
+
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("http {} {}", first, USER_AGENT)
+    );
+}
+
+#[tokio::test]
+async fn test_call_authority_keeps_secure_scheme() {
+    let config = ClientConfig::builder()
+        .connector(SecureConnector)
+        .build()
+        .unwrap();
+    let resolver = StaticResolver::new([Address::Named("memory".to_string())]);
+    let channel =
+        Channel::resolve_with("dns:///secure.example.com:443", &config, Box::new(resolver))
+            .unwrap();
+    // Record the URI the client builds, before the channel fills it in.
+    let built = Arc::new(Mutex::new(None));
+    let recorder = built.clone();
+    let service = channel.map_request(move |request: Request<Body>| {
+        *recorder.lock().unwrap() = Some(request.uri().to_string());
+        request
+    });
+    let client = Client::with_service(service, Some(config));
+
+    let options = CallOptions::new().with_authority("canary.example.com");
+    assert_eq!(
+        describe(&client, options).await,
+        format!("https canary.example.com {}", USER_AGENT)
+    );
+    assert_eq!(
+        built.lock().unwrap().as_deref(),
+        Some("https://canary.example.com/test.service/Describe")
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_unix_socket_uses_localhost() {
+    let path = std::env::temp_dir().join(format!("grpc-headers-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        serve_connection(socket, handle_describe).await;
+    });
+
+    let client = Client::connect(format!("unix:{}", path.display()), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("http localhost {}", USER_AGENT)
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn test_user_agent_prefix() {
+    let addr = serve(handle_describe).await;
+    let config = ClientConfig::builder()
+        .authority("api.example.com".to_string())
+        .user_agent("my-app/1.2".to_string())
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    assert_eq!(
+        describe(&client, CallOptions::new()).await,
+        format!("http api.example.com my-app/1.2 {}", USER_AGENT)
+    );
+}
+
+#[test]
+fn test_invalid_authority_and_user_agent_are_rejected() {
+    let builders = [
+        ClientConfig::builder().authority("not an authority".to_string()),
+        ClientConfig::builder().user_agent("my-app\n".to_string()),
+    ];
+    for builder in builders {
+        assert!(matches!(builder.build(), Err(Error::Config(_))));
+    }
+}
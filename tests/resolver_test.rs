@@ -0,0 +1,132 @@
+// tests/resolver_test.rs
+use std::time::Duration;
+
+use grpc_project::{
+    channel::{Address, BackoffConfig, LoadBalancingPolicy, StaticResolver},
+    client::{CallOptions, Client, ClientConfig, ClientConfigBuilder},
+    Channel, Code, Error,
+};
+
+mod common;
+use common::{send_message, TestRequest, TestResponse, TestServer};
+
+fn test_config() -> ClientConfig {
+    ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(200))
+        .connection_backoff(BackoffConfig {
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+/// A server that answers every call with its own name.
+async fn named_server(name: &'static str) -> TestServer {
+    TestServer::start(move |_request, respond| async move {
+        let response = TestResponse {
+            message: name.to_string(),
+        };
+        send_message(respond, &response);
+    })
+    .await
+}
+
+async fn call(client: &Client, options: CallOptions) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "who".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Who", request, options)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+#[tokio::test]
+async fn test_ipv4_target_lists_addresses() {
+    let a = named_server("a").await;
+    let b = named_server("b").await;
+    let target = format!("ipv4:{},{}", a.local_addr, b.local_addr);
+    let config = ClientConfigBuilder::from_config(test_config())
+        .load_balancing_policy(LoadBalancingPolicy::RoundRobin)
+        .build()
+        .unwrap();
+
+    let channel = Channel::connect_lazy(&target, &config).unwrap();
+    assert_eq!(
+        channel.addrs(),
+        vec![Address::Tcp(a.local_addr), Address::Tcp(b.local_addr)]
+    );
+
+    let client = Client::from_channel(channel, Some(config));
+    call(&client, CallOptions::new()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut names = Vec::new();
+    for _ in 0..4 {
+        names.push(call(&client, CallOptions::new()).await.unwrap());
+    }
+    names.sort();
+    assert_eq!(names, ["a", "a", "b", "b"]);
+}
+
+#[tokio::test]
+async fn test_dns_target_resolves_localhost() {
+    let server = named_server("a").await;
+    let target = format!("dns:///localhost:{}", server.local_addr.port());
+
+    let client = Client::connect(&target, Some(test_config())).await.unwrap();
+    assert_eq!(call(&client, CallOptions::new()).await.unwrap(), "a");
+}
+
+#[tokio::test]
+async fn test_static_resolver_pushes_updates() {
+    let a = named_server("a").await;
+    let b = named_server("b").await;
+    let resolver = StaticResolver::new([a.local_addr]);
+    let channel =
+        Channel::resolve_with("static:///test", &test_config(), Box::new(resolver.clone()))
+            .unwrap();
+    let client = Client::from_channel(channel.clone(), Some(test_config()));
+
+    assert_eq!(call(&client, CallOptions::new()).await.unwrap(), "a");
+
+    resolver.update([b.local_addr]);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(channel.addrs(), vec![Address::Tcp(b.local_addr)]);
+    assert_eq!(call(&client, CallOptions::new()).await.unwrap(), "b");
+}
+
+#[tokio::test]
+async fn test_resolution_failure_is_unavailable() {
+    let resolver = StaticResolver::new(Vec::<Address>::new());
+    resolver.fail("no such host");
+    let channel =
+        Channel::resolve_with("static:///test", &test_config(), Box::new(resolver.clone()))
+            .unwrap();
+    let client = Client::from_channel(channel, Some(test_config()));
+
+    match call(&client, CallOptions::new()).await {
+        Err(Error::Status(status)) => {
+            assert_eq!(status.code(), Code::Unavailable);
+            assert!(status.message().contains("no such host"));
+        }
+        other => panic!("expected Unavailable, got {:?}", other),
+    }
+
+    // A wait-for-ready call waits for the resolver to recover.
+    let server = named_server("a").await;
+    let options = CallOptions::new().with_wait_for_ready(true);
+    let pending = tokio::spawn(async move { call(&client, options).await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    resolver.update([server.local_addr]);
+
+    assert_eq!(pending.await.unwrap().unwrap(), "a");
+}
+
+#[tokio::test]
+async fn test_unsupported_target_scheme() {
+    let result = Channel::connect_lazy("carrier-pigeon:///coop", &test_config());
+    assert!(matches!(result, Err(Error::Protocol(_))));
+}
@@ -0,0 +1,208 @@
+// tests/retry_test.rs
+mod common;
+
+use bytes::Bytes;
+use common::*;
+use grpc_project::client::{ClientConfig, ClientConfigBuilder, RetryPolicy};
+use grpc_project::{Client, Code, Error};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn retry_config(max_attempts: u32) -> ClientConfig {
+    ClientConfig::builder()
+        .retry_policy(RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            backoff_multiplier: 2.0,
+            retryable_status_codes: vec![Code::Unavailable],
+        })
+        .build()
+        .unwrap()
+}
+
+async fn call(client: &Client) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "retry".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+fn status_code(result: Result<String, Error>) -> Code {
+    match result {
+        Err(Error::Status(status)) => status.code(),
+        other => panic!("expected status error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_retry_until_success() {
+    let (addr, attempts) = flaky_server(2, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, Some(retry_config(3))).await.unwrap();
+
+    assert_eq!(call(&client).await.unwrap(), "retry");
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![None, Some("1".to_string()), Some("2".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_status_is_returned_without_policy() {
+    let (addr, attempts) = flaky_server(1, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let result = call(&client).await;
+    match result {
+        Err(Error::Status(status)) => {
+            assert_eq!(status.code(), Code::Unavailable);
+            assert_eq!(status.message(), "test failure");
+        }
+        other => panic!("expected status error, got {:?}", other),
+    }
+    assert_eq!(attempts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_non_retryable_code_is_not_retried() {
+    let (addr, attempts) = flaky_server(1, 13, &[]).await;
+    let client = Client::connect(&addr, Some(retry_config(3))).await.unwrap();
+
+    assert_eq!(status_code(call(&client).await), Code::Internal);
+    assert_eq!(attempts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_last_status_after_max_attempts() {
+    let (addr, attempts) = flaky_server(usize::MAX, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, Some(retry_config(3))).await.unwrap();
+
+    assert_eq!(status_code(call(&client).await), Code::Unavailable);
+    assert_eq!(attempts.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_max_attempts_is_capped() {
+    let (addr, attempts) = flaky_server(usize::MAX, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, Some(retry_config(10)))
+        .await
+        .unwrap();
+
+    assert_eq!(status_code(call(&client).await), Code::Unavailable);
+    assert_eq!(attempts.lock().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_pushback_delays_retry() {
+    let (addr, attempts) = flaky_server(1, UNAVAILABLE, &[("grpc-retry-pushback-ms", "300")]).await;
+    let client = Client::connect(&addr, Some(retry_config(3))).await.unwrap();
+
+    let start = Instant::now();
+    assert_eq!(call(&client).await.unwrap(), "retry");
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    assert_eq!(attempts.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_negative_pushback_stops_retries() {
+    let (addr, attempts) = flaky_server(1, UNAVAILABLE, &[("grpc-retry-pushback-ms", "-1")]).await;
+    let client = Client::connect(&addr, Some(retry_config(3))).await.unwrap();
+
+    assert_eq!(status_code(call(&client).await), Code::Unavailable);
+    assert_eq!(attempts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_retries_respect_deadline() {
+    let (addr, attempts) = flaky_server(
+        usize::MAX,
+        UNAVAILABLE,
+        &[("grpc-retry-pushback-ms", "500")],
+    )
+    .await;
+    let config = ClientConfigBuilder::from_config(retry_config(5))
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    let start = Instant::now();
+    assert_eq!(status_code(call(&client).await), Code::Unavailable);
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert_eq!(attempts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_deadline_exceeded() {
+    let addr = serve(|request, respond| async move {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        handle_echo(request, respond).await;
+    })
+    .await;
+    let config = ClientConfig::builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    assert_eq!(status_code(call(&client).await), Code::DeadlineExceeded);
+}
+
+#[tokio::test]
+async fn test_grpc_timeout_header_is_sent() {
+    let timeout = Arc::new(Mutex::new(None));
+    let seen = timeout.clone();
+    let addr = serve(move |request: Request<RecvStream>, respond| {
+        let seen = seen.clone();
+        async move {
+            *seen.lock().unwrap() = request
+                .headers()
+                .get("grpc-timeout")
+                .map(|value| value.to_str().unwrap().to_string());
+            handle_echo(request, respond).await;
+        }
+    })
+    .await;
+    let config = ClientConfig::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    call(&client).await.unwrap();
+    let header = timeout.lock().unwrap().clone().unwrap();
+    let millis: u64 = header.strip_suffix('m').unwrap().parse().unwrap();
+    assert!(millis > 9_000 && millis <= 10_000);
+}
+
+#[tokio::test]
+async fn test_committed_attempt_is_not_retried() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let handler = move |_request: Request<RecvStream>, mut respond: SendResponse<Bytes>| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            // Response headers commit the call before the status arrives.
+            let response = http::Response::builder()
+                .status(200)
+                .header("content-type", "application/grpc")
+                .body(())
+                .unwrap();
+            let mut stream = respond.send_response(response, false).unwrap();
+            let mut trailers = http::HeaderMap::new();
+            trailers.insert("grpc-status", UNAVAILABLE.into());
+            stream.send_trailers(trailers).unwrap();
+        }
+    };
+    let client = serve_in_process(handler, Some(retry_config(3)));
+
+    assert_eq!(status_code(call(&client).await), Code::Unavailable);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
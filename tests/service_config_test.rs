@@ -0,0 +1,129 @@
+// tests/service_config_test.rs
+mod common;
+
+use common::*;
+use grpc_project::client::{ClientConfig, ServiceConfig};
+use grpc_project::{Client, Code, Error};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SERVICE_CONFIG: &str = r#"{
+    "methodConfig": [
+        {
+            "name": [{ "service": "test.service" }],
+            "timeout": "0.1s"
+        },
+        {
+            "name": [{ "service": "test.service", "method": "Retry" }],
+            "timeout": "10s",
+            "maxResponseMessageBytes": "1024",
+            "retryPolicy": {
+                "maxAttempts": 4,
+                "initialBackoff": "0.01s",
+                "maxBackoff": "0.05s",
+                "backoffMultiplier": 2,
+                "retryableStatusCodes": ["UNAVAILABLE", 8]
+            }
+        },
+        {
+            "name": [{ "service": "test.service", "method": "Small" }],
+            "maxRequestMessageBytes": 16
+        }
+    ],
+    "retryThrottling": { "maxTokens": 100, "tokenRatio": 0.5 }
+}"#;
+
+/// Sleeps for `delay`, then fails the first two calls with UNAVAILABLE and
+/// echoes the rest.
+async fn server(delay: Duration) -> (String, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let addr = serve(move |request, respond| {
+        let counter = counter.clone();
+        async move {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(delay).await;
+            if call < 2 {
+                send_status(respond, 14, &[]);
+            } else {
+                handle_echo(request, respond).await;
+            }
+        }
+    })
+    .await;
+    (addr, calls)
+}
+
+async fn connect(addr: &str) -> Client {
+    let config = ClientConfig::builder()
+        .service_config(ServiceConfig::from_json(SERVICE_CONFIG).unwrap())
+        .build()
+        .unwrap();
+    Client::connect(addr, Some(config)).await.unwrap()
+}
+
+async fn call(client: &Client, method: &str, message: &str) -> Result<String, Error> {
+    let request = TestRequest {
+        message: message.to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>(method, request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+#[tokio::test]
+async fn test_method_retry_policy_applies() {
+    let (addr, calls) = server(Duration::ZERO).await;
+    let client = connect(&addr).await;
+
+    assert_eq!(
+        call(&client, "/test.service/Retry", "hi").await.unwrap(),
+        "hi"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_service_level_timeout_applies() {
+    let (addr, calls) = server(Duration::from_millis(500)).await;
+    let client = connect(&addr).await;
+
+    match call(&client, "/test.service/Other", "hi").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::DeadlineExceeded),
+        other => panic!("expected deadline, got {:?}", other),
+    }
+    // The service-level entry has no retry policy.
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_unmatched_method_uses_client_defaults() {
+    let (addr, calls) = server(Duration::ZERO).await;
+    let client = connect(&addr).await;
+
+    match call(&client, "/other.service/Call", "hi").await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected status error, got {:?}", other),
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_method_message_size_limits() {
+    let (addr, _) = server(Duration::ZERO).await;
+    let client = connect(&addr).await;
+
+    let large = "x".repeat(64);
+    assert!(matches!(
+        call(&client, "/test.service/Small", &large).await,
+        Err(Error::MessageTooLarge(_))
+    ));
+
+    let larger = "x".repeat(2048);
+    match call(&client, "/test.service/Retry", &larger).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::ResourceExhausted),
+        other => panic!("expected ResourceExhausted, got {:?}", other),
+    }
+}
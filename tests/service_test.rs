@@ -0,0 +1,113 @@
+// tests/service_test.rs
+use bytes::{Bytes, BytesMut};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::{Request, Response};
+use prost::Message;
+use std::time::Duration;
+use tower::{service_fn, ServiceBuilder};
+
+use grpc_project::{
+    client::{Client, ClientConfig},
+    codec::{Frame, GrpcCodec},
+    Body, Channel, Error,
+};
+
+mod common;
+use common::{handle_request, in_process_server, TestRequest, TestResponse};
+
+fn test_request() -> TestRequest {
+    TestRequest {
+        message: "test request".to_string(),
+    }
+}
+
+/// Holds on to every stream without ever responding.
+async fn silent(_request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    std::future::pending::<()>().await;
+    drop(respond);
+}
+
+#[tokio::test]
+async fn test_layered_connection() {
+    let config = ClientConfig::default();
+    let channel = Channel::in_process(in_process_server(handle_request), &config);
+
+    let service = ServiceBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .concurrency_limit(10)
+        .service(channel);
+    let client = Client::with_service(service, Some(config));
+
+    let response = client
+        .unary::<TestRequest, TestResponse>("/test.service/TestMethod", test_request(), None)
+        .await
+        .unwrap();
+    assert_eq!(response.into_body().message, "test response");
+}
+
+#[tokio::test]
+async fn test_timeout_layer() {
+    let config = ClientConfig::default();
+    let channel = Channel::in_process(in_process_server(silent), &config);
+
+    let service = ServiceBuilder::new()
+        .timeout(Duration::from_millis(50))
+        .service(channel);
+    let client = Client::with_service(service, Some(config));
+
+    let result = client
+        .unary::<TestRequest, TestResponse>("/test.service/TestMethod", test_request(), None)
+        .await;
+    assert!(
+        matches!(result, Err(Error::Service(_))),
+        "Expected timeout error, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_inner_errors_pass_through_layers() {
+    let channel_config = ClientConfig::builder().max_message_size(8).build().unwrap();
+    let channel = Channel::in_process(in_process_server(handle_request), &channel_config);
+
+    let service = ServiceBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .service(channel);
+    let client = Client::with_service(service, None);
+
+    let result = client
+        .unary::<TestRequest, TestResponse>("/test.service/TestMethod", test_request(), None)
+        .await;
+    assert!(
+        matches!(result, Err(Error::MessageTooLarge(_))),
+        "Expected MessageTooLarge, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_custom_service() {
+    let service = service_fn(|request: Request<Body>| async move {
+        assert_eq!(request.uri().path(), "/test.service/TestMethod");
+        assert_eq!(request.headers()["content-type"], "application/grpc");
+
+        let message = TestResponse {
+            message: "from service_fn".to_string(),
+        };
+        let mut buf = BytesMut::new();
+        GrpcCodec::new().encode(
+            Frame::new(Bytes::from(message.encode_to_vec()), false),
+            &mut buf,
+        )?;
+
+        Ok::<_, Error>(Response::new(Body::from(buf.freeze())))
+    });
+    let client = Client::with_service(service, None);
+
+    let response = client
+        .unary::<TestRequest, TestResponse>("/test.service/TestMethod", test_request(), None)
+        .await
+        .unwrap();
+    assert_eq!(response.into_body().message, "from service_fn");
+}
@@ -0,0 +1,164 @@
+// tests/shutdown_test.rs
+use bytes::Bytes;
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+use grpc_project::{
+    channel::ConnectivityState,
+    client::{Client, ClientConfig},
+    transport::{Connection, Http2Transport},
+    Channel, Error,
+};
+
+mod common;
+use common::{echo, read_message, send_message, serve, TestRequest, TestResponse};
+
+const SLOW_CALL: Duration = Duration::from_millis(300);
+
+async fn slow_echo(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let test_request: TestRequest = read_message(request.into_body()).await;
+    tokio::time::sleep(SLOW_CALL).await;
+    send_message(
+        respond,
+        &TestResponse {
+            message: test_request.message,
+        },
+    );
+}
+
+/// Gracefully shuts down the first connection as soon as its first request
+/// arrives; later connections are served normally.
+async fn start_goaway_server(connections: Arc<AtomicUsize>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let index = connections.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                let mut connection = h2::server::handshake(socket).await.unwrap();
+                let mut sent_goaway = false;
+                while let Some(Ok((request, respond))) = connection.accept().await {
+                    tokio::spawn(slow_echo(request, respond));
+                    if index == 0 && !sent_goaway {
+                        connection.graceful_shutdown();
+                        sent_goaway = true;
+                    }
+                }
+            });
+        }
+    });
+
+    addr
+}
+
+#[tokio::test]
+async fn test_shutdown_lets_in_flight_calls_finish() {
+    let addr = serve(slow_echo).await;
+    let channel = Channel::connect(&addr, &ClientConfig::default())
+        .await
+        .unwrap();
+    let client = Client::from_channel(channel.clone(), None);
+
+    let in_flight = tokio::spawn({
+        let client = client.clone();
+        async move { echo(&client, "in flight").await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    channel.shutdown().await;
+    assert_eq!(channel.state(), ConnectivityState::Shutdown);
+    assert_eq!(in_flight.await.unwrap().unwrap(), "in flight");
+
+    let result = echo(&client, "too late").await;
+    assert!(
+        matches!(result, Err(Error::ConnectionClosed)),
+        "Expected ConnectionClosed, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_drain_timeout_cuts_off_slow_calls() {
+    let addr = serve(|request, respond| async move {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        slow_echo(request, respond).await;
+    })
+    .await;
+    let config = ClientConfig::builder()
+        .drain_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    let in_flight = tokio::spawn({
+        let client = client.clone();
+        async move { echo(&client, "slow").await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let started = Instant::now();
+    client.close().await;
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(in_flight.await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_close_idle_client() {
+    let addr = serve(slow_echo).await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let started = Instant::now();
+    client.close().await;
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(matches!(
+        echo(&client, "closed").await,
+        Err(Error::ConnectionClosed)
+    ));
+}
+
+#[tokio::test]
+async fn test_goaway_moves_new_calls_to_new_connection() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let addr = start_goaway_server(connections.clone()).await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    let in_flight = tokio::spawn({
+        let client = client.clone();
+        async move { echo(&client, "first").await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(echo(&client, "second").await.unwrap(), "second");
+    assert_eq!(in_flight.await.unwrap().unwrap(), "first");
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
+
+// `close` still has to stop the deprecated byte-pipe `send`.
+#[allow(deprecated)]
+#[tokio::test]
+async fn test_transport_close_rejects_new_sends() {
+    let addr = serve(slow_echo).await;
+    let socket = tokio::net::TcpStream::connect(addr.trim_start_matches("http://"))
+        .await
+        .unwrap();
+    let (send_request, h2_connection) = h2::client::handshake(socket).await.unwrap();
+    let driver = tokio::spawn(h2_connection);
+
+    let mut connection = Connection::new(Http2Transport::new(send_request));
+    connection.close().await.unwrap();
+
+    assert!(matches!(
+        connection.send(Bytes::from_static(b"data")).await,
+        Err(Error::ConnectionClosed)
+    ));
+
+    // With the only sender gone, h2 closes the idle connection on its own.
+    let closed = tokio::time::timeout(Duration::from_secs(1), driver).await;
+    assert!(closed.is_ok());
+}
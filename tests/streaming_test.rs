@@ -0,0 +1,282 @@
+// tests/streaming_test.rs
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use futures::channel::mpsc;
+use futures::stream;
+use grpc_project::{
+    client::{CallOptions, Client, ClientConfig, Streaming},
+    CancellationToken, Code, Error,
+};
+use h2::server::SendResponse;
+use h2::RecvStream;
+use http::Request;
+
+mod common;
+use common::{
+    frame, next_message, send_status, send_trailers, serve, serve_in_process, start_response,
+    TestRequest, TestResponse,
+};
+
+fn request(message: &str) -> TestRequest {
+    TestRequest {
+        message: message.to_string(),
+    }
+}
+
+fn response(message: impl Into<String>) -> TestResponse {
+    TestResponse {
+        message: message.into(),
+    }
+}
+
+fn status_code<T: std::fmt::Debug>(result: Result<T, Error>) -> Code {
+    match result {
+        Err(Error::Status(status)) => status.code(),
+        other => panic!("expected status error, got {:?}", other),
+    }
+}
+
+/// Answers one request with three numbered copies of its message.
+async fn repeat(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let mut body = request.into_body();
+    let hello: TestRequest = next_message(&mut body, &mut BytesMut::new()).await.unwrap();
+    let mut send_stream = start_response(respond);
+    for i in 1..=3 {
+        let reply = response(format!("{} {}", hello.message, i));
+        send_stream.send_data(frame(&reply), false).unwrap();
+    }
+    send_trailers(&mut send_stream, 0);
+}
+
+/// Replies once the client ends its stream, joining every message it sent.
+async fn join(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let (mut body, mut buf) = (request.into_body(), BytesMut::new());
+    let mut messages = Vec::new();
+    while let Some(message) = next_message::<TestRequest>(&mut body, &mut buf).await {
+        messages.push(message.message);
+    }
+    let mut send_stream = start_response(respond);
+    send_stream
+        .send_data(frame(&response(messages.join(","))), false)
+        .unwrap();
+    send_trailers(&mut send_stream, 0);
+}
+
+/// Echoes each message as soon as it arrives.
+async fn echo_each(request: Request<RecvStream>, respond: SendResponse<Bytes>) {
+    let (mut body, mut buf) = (request.into_body(), BytesMut::new());
+    let mut send_stream = start_response(respond);
+    while let Some(message) = next_message::<TestRequest>(&mut body, &mut buf).await {
+        let reply = response(message.message);
+        send_stream.send_data(frame(&reply), false).unwrap();
+    }
+    send_trailers(&mut send_stream, 0);
+}
+
+async fn collect(messages: &mut Streaming<TestResponse>) -> Result<Vec<String>, Error> {
+    let mut collected = Vec::new();
+    while let Some(message) = messages.message().await? {
+        collected.push(message.message);
+    }
+    Ok(collected)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_server_streaming_reads_every_message() {
+    let client = serve_in_process(repeat, None);
+
+    let response = client
+        .server_streaming::<TestRequest, TestResponse>("/test.service/Repeat", request("hi"), None)
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert!(messages.trailers().is_none());
+    assert_eq!(
+        collect(&mut messages).await.unwrap(),
+        ["hi 1", "hi 2", "hi 3"]
+    );
+    assert_eq!(messages.trailers().unwrap()["grpc-status"], "0");
+    assert!(messages.message().await.unwrap().is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_server_streaming_fails_with_the_trailer_status() {
+    let client = serve_in_process(
+        |_request, respond| async move {
+            let mut send_stream = start_response(respond);
+            send_stream
+                .send_data(frame(&response("first")), false)
+                .unwrap();
+            send_trailers(&mut send_stream, Code::DataLoss as i32);
+        },
+        None,
+    );
+
+    let response = client
+        .server_streaming::<TestRequest, TestResponse>("/test.service/Repeat", request("hi"), None)
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert_eq!(messages.message().await.unwrap().unwrap().message, "first");
+    assert_eq!(status_code(messages.message().await), Code::DataLoss);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_trailers_only_response_fails_the_call() {
+    let client = serve_in_process(
+        |_request, respond| async move { send_status(respond, Code::NotFound as i32, &[]) },
+        None,
+    );
+
+    let result = client
+        .server_streaming::<TestRequest, TestResponse>("/test.service/Repeat", request("hi"), None)
+        .await;
+    assert_eq!(status_code(result), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_client_streaming_sends_every_message() {
+    let addr = serve(join).await;
+    let client = Client::connect(&addr, None).await.unwrap();
+
+    // Well past the initial 64 KiB window, so sending waits for capacity.
+    let chunk = "x".repeat(4096);
+    let requests: Vec<_> = (0..50).map(|_| request(&chunk)).collect();
+    let response = client
+        .client_streaming::<_, _, TestResponse>("/test.service/Join", stream::iter(requests), None)
+        .await
+        .unwrap();
+    let joined = response.into_body().message;
+    assert_eq!(joined.split(',').count(), 50);
+    assert!(joined.split(',').all(|message| message == chunk));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_client_streaming_with_no_messages() {
+    let client = serve_in_process(join, None);
+
+    let requests = stream::empty::<TestRequest>();
+    let response = client
+        .client_streaming::<_, _, TestResponse>("/test.service/Join", requests, None)
+        .await
+        .unwrap();
+    assert_eq!(response.into_body().message, "");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_oversized_request_message_fails_the_call() {
+    let config = ClientConfig::builder()
+        .max_message_size(1024)
+        .build()
+        .unwrap();
+    let client = serve_in_process(join, Some(config));
+
+    let requests = stream::iter([request("small"), request(&"x".repeat(2048))]);
+    let result = client
+        .client_streaming::<_, _, TestResponse>("/test.service/Join", requests, None)
+        .await;
+    assert!(
+        matches!(result, Err(Error::MessageTooLarge(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_bidi_streaming_interleaves_messages() {
+    let client = serve_in_process(echo_each, None);
+    let (sender, requests) = mpsc::unbounded();
+
+    // Each reply arrives before the next request is sent.
+    sender.unbounded_send(request("ping")).unwrap();
+    let response = client
+        .streaming::<_, _, TestResponse>("/test.service/Echo", requests, None)
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert_eq!(messages.message().await.unwrap().unwrap().message, "ping");
+
+    sender.unbounded_send(request("pong")).unwrap();
+    assert_eq!(messages.message().await.unwrap().unwrap().message, "pong");
+
+    drop(sender);
+    assert!(messages.message().await.unwrap().is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_deadline_covers_reading_the_stream() {
+    let client = serve_in_process(
+        |_request, respond| async move {
+            let mut send_stream = start_response(respond);
+            send_stream
+                .send_data(frame(&response("first")), false)
+                .unwrap();
+            // Stall the stream, but keep it open.
+            std::future::pending::<()>().await;
+        },
+        None,
+    );
+
+    let options = CallOptions::new().with_timeout(Duration::from_secs(1));
+    let response = client
+        .server_streaming::<TestRequest, TestResponse>(
+            "/test.service/Repeat",
+            request("hi"),
+            options,
+        )
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert_eq!(messages.message().await.unwrap().unwrap().message, "first");
+    assert_eq!(
+        status_code(messages.message().await),
+        Code::DeadlineExceeded
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cancellation_ends_a_bidi_stream() {
+    let client = serve_in_process(echo_each, None);
+    let (sender, requests) = mpsc::unbounded();
+    let token = CancellationToken::new();
+
+    sender.unbounded_send(request("ping")).unwrap();
+    let options = CallOptions::new().with_cancellation(token.clone());
+    let response = client
+        .streaming::<_, _, TestResponse>("/test.service/Echo", requests, options)
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert_eq!(messages.message().await.unwrap().unwrap().message, "ping");
+
+    token.cancel();
+    assert_eq!(status_code(messages.message().await), Code::Cancelled);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_oversized_response_message_is_refused() {
+    let config = ClientConfig::builder()
+        .max_message_size(1024)
+        .build()
+        .unwrap();
+    let client = serve_in_process(
+        |_request, respond| async move {
+            let mut send_stream = start_response(respond);
+            let reply = response("x".repeat(2048));
+            send_stream.send_data(frame(&reply), false).unwrap();
+            send_trailers(&mut send_stream, 0);
+        },
+        Some(config),
+    );
+
+    let response = client
+        .server_streaming::<TestRequest, TestResponse>("/test.service/Repeat", request("hi"), None)
+        .await
+        .unwrap();
+    let mut messages = response.into_body();
+    assert_eq!(
+        status_code(messages.message().await),
+        Code::ResourceExhausted
+    );
+}
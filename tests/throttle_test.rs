@@ -0,0 +1,102 @@
+// tests/throttle_test.rs
+mod common;
+
+use common::*;
+use grpc_project::client::{ClientConfig, HedgingPolicy, RetryPolicy, RetryThrottling};
+use grpc_project::{Client, Code, Error};
+use std::time::Duration;
+
+const METHOD: &str = "/test.service/Echo";
+
+fn throttled_config(max_tokens: u32, token_ratio: f64) -> ClientConfig {
+    ClientConfig::builder()
+        .retry_policy(RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..RetryPolicy::default()
+        })
+        .retry_throttling(RetryThrottling {
+            max_tokens,
+            token_ratio,
+        })
+        .build()
+        .unwrap()
+}
+
+async fn call(client: &Client) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "throttle".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>(METHOD, request, None)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+#[tokio::test]
+async fn test_throttling_limits_retries() {
+    let (addr, attempts) = flaky_server(usize::MAX, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, Some(throttled_config(4, 0.1)))
+        .await
+        .unwrap();
+
+    // 4 tokens: the first failure leaves 3 (retry allowed), the second
+    // leaves 2, which is not above half.
+    assert!(call(&client).await.is_err());
+    assert_eq!(attempts.lock().unwrap().len(), 2);
+
+    // The bucket is shared with clones, so retries stay disabled.
+    let clone = client.clone();
+    match call(&clone).await {
+        Err(Error::Status(status)) => assert_eq!(status.code(), Code::Unavailable),
+        other => panic!("expected status error, got {:?}", other),
+    }
+    assert_eq!(attempts.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_successes_refill_tokens() {
+    let (addr, attempts) = flaky_server(2, UNAVAILABLE, &[]).await;
+    let client = Client::connect(&addr, Some(throttled_config(4, 1.0)))
+        .await
+        .unwrap();
+
+    // Two failures drain the bucket to half, so the call gives up.
+    assert!(call(&client).await.is_err());
+    assert_eq!(attempts.lock().unwrap().len(), 2);
+
+    // A success refills a token, re-enabling retries.
+    assert_eq!(call(&client).await.unwrap(), "throttle");
+    assert_eq!(attempts.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_retries_without_throttling() {
+    let (addr, attempts) = flaky_server(3, UNAVAILABLE, &[]).await;
+    let mut config = throttled_config(4, 0.1);
+    config.retry_throttling = None;
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    assert_eq!(call(&client).await.unwrap(), "throttle");
+    assert_eq!(attempts.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_throttling_disables_hedging() {
+    let (addr, attempts) = flaky_server(usize::MAX, UNAVAILABLE, &[]).await;
+    let mut config = throttled_config(2, 0.1);
+    config.hedging_policies.insert(
+        METHOD.to_string(),
+        HedgingPolicy {
+            max_attempts: 5,
+            hedging_delay: Duration::from_millis(10),
+            non_fatal_status_codes: vec![Code::Unavailable],
+        },
+    );
+    let client = Client::connect(&addr, Some(config)).await.unwrap();
+
+    // 2 tokens: the first failure leaves 1, which stops further hedges.
+    assert!(call(&client).await.is_err());
+    assert_eq!(attempts.lock().unwrap().len(), 1);
+}
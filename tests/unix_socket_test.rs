@@ -0,0 +1,76 @@
+// tests/unix_socket_test.rs
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use grpc_project::{
+    channel::{Address, ConnectivityState},
+    client::{Client, ClientConfig},
+    Channel, Error,
+};
+use tokio::net::UnixListener;
+use tokio::task::{JoinHandle, JoinSet};
+
+mod common;
+use common::{echo, handle_echo, serve_connection};
+
+fn socket_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("grpc-{}-{}.sock", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Serves echo calls on a Unix socket at `path`.
+fn serve_unix(path: &Path) -> JoinHandle<()> {
+    let listener = UnixListener::bind(path).unwrap();
+    tokio::spawn(async move {
+        let mut connections = JoinSet::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            connections.spawn(serve_connection(socket, handle_echo));
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_unary_over_unix_socket() {
+    let path = socket_path("unary");
+    let server = serve_unix(&path);
+
+    let target = format!("unix:{}", path.display());
+    let client = Client::connect(&target, None).await.unwrap();
+    assert_eq!(echo(&client, "over unix").await.unwrap(), "over unix");
+
+    server.abort();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_lazy_unix_target_with_absolute_path() {
+    let path = socket_path("lazy");
+    let target = format!("unix://{}", path.display());
+    let channel = Channel::connect_lazy(&target, &ClientConfig::default()).unwrap();
+    assert_eq!(channel.addrs(), vec![Address::Unix(path.clone())]);
+    assert_eq!(channel.state(), ConnectivityState::Idle);
+
+    // The socket only has to exist once the first call is made.
+    let server = serve_unix(&path);
+    let client = Client::from_channel(channel.clone(), None);
+    assert_eq!(echo(&client, "lazy").await.unwrap(), "lazy");
+    assert_eq!(channel.state(), ConnectivityState::Ready);
+
+    server.abort();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_missing_socket_fails_to_connect() {
+    let path = socket_path("missing");
+    let config = ClientConfig::builder()
+        .connect_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let result = Client::connect(format!("unix:{}", path.display()), Some(config)).await;
+    assert!(matches!(result, Err(Error::Io(_))), "got {:?}", result);
+}
@@ -0,0 +1,101 @@
+// tests/wait_for_ready_test.rs
+use std::time::{Duration, Instant};
+
+use grpc_project::{
+    client::{CallOptions, Client, ClientConfigBuilder, ServiceConfig},
+    Code, Error,
+};
+
+mod common;
+use common::{
+    fast_backoff_config, handle_echo, unused_addr, TestRequest, TestResponse, TestServer,
+};
+
+async fn echo(client: &Client, options: CallOptions) -> Result<String, Error> {
+    let request = TestRequest {
+        message: "ready".to_string(),
+    };
+    client
+        .unary::<TestRequest, TestResponse>("/test.service/Echo", request, options)
+        .await
+        .map(|response| response.into_body().message)
+}
+
+fn assert_code(result: Result<String, Error>, code: Code) {
+    match result {
+        Err(Error::Status(status)) => assert_eq!(status.code(), code),
+        other => panic!("expected {}, got {:?}", code, other),
+    }
+}
+
+#[tokio::test]
+async fn test_fail_fast_by_default() {
+    let addr = unused_addr().await;
+    let client =
+        Client::connect_lazy(format!("http://{}", addr), Some(fast_backoff_config())).unwrap();
+
+    let start = Instant::now();
+    assert_code(echo(&client, CallOptions::new()).await, Code::Unavailable);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_wait_for_ready_waits_for_server() {
+    let addr = unused_addr().await;
+    let client =
+        Client::connect_lazy(format!("http://{}", addr), Some(fast_backoff_config())).unwrap();
+
+    let call =
+        tokio::spawn(
+            async move { echo(&client, CallOptions::new().with_wait_for_ready(true)).await },
+        );
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!call.is_finished());
+
+    let _server = TestServer::start_on(addr, handle_echo).await;
+    assert_eq!(call.await.unwrap().unwrap(), "ready");
+}
+
+#[tokio::test]
+async fn test_wait_for_ready_honors_deadline() {
+    let addr = unused_addr().await;
+    let config = ClientConfigBuilder::from_config(fast_backoff_config())
+        .timeout(Duration::from_millis(300))
+        .build()
+        .unwrap();
+    let client = Client::connect_lazy(format!("http://{}", addr), Some(config)).unwrap();
+
+    let start = Instant::now();
+    assert_code(
+        echo(&client, CallOptions::new().with_wait_for_ready(true)).await,
+        Code::DeadlineExceeded,
+    );
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_service_config_wait_for_ready() {
+    let addr = unused_addr().await;
+    let service_config = ServiceConfig::from_json(
+        r#"{ "methodConfig": [{ "name": [{ "service": "test.service" }], "waitForReady": true }] }"#,
+    )
+    .unwrap();
+    let config = ClientConfigBuilder::from_config(fast_backoff_config())
+        .timeout(Duration::from_millis(300))
+        .service_config(service_config)
+        .build()
+        .unwrap();
+    let client = Client::connect_lazy(format!("http://{}", addr), Some(config)).unwrap();
+
+    assert_code(
+        echo(&client, CallOptions::new()).await,
+        Code::DeadlineExceeded,
+    );
+
+    // The call option takes precedence over the service config.
+    assert_code(
+        echo(&client, CallOptions::new().with_wait_for_ready(false)).await,
+        Code::Unavailable,
+    );
+}